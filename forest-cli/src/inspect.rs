@@ -0,0 +1,106 @@
+use forest_lib::{forest::Forest, inspect::CellInfo};
+use std::error::Error;
+
+use crate::{draw_info, parse_arguments};
+
+const USAGE: &str = "usage: forest-cli inspect [seed] [width] [height] --at x,y [--month m] [rule flags]";
+
+/// Runs a forest to the requested month and describes one of its cells. Any arguments
+/// other than `--at` and `--month` are read as for a normal run.
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut at = None;
+    let mut month = 0;
+    let mut rest = vec!["inspect".to_string()];
+
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--at" => match args_iter.next() {
+                Some(value) => at = Some(parse_position(value)?),
+                None => return Err("missing value for --at".into()),
+            },
+            "--month" => match args_iter.next() {
+                Some(value) => month = value.parse()?,
+                None => return Err("missing value for --month".into()),
+            },
+            _ => rest.push(arg.clone()),
+        }
+    }
+
+    let Some((x, y)) = at else {
+        return Err(USAGE.into());
+    };
+
+    let config = parse_arguments(&rest)?;
+    let mut forest =
+        Forest::try_with_rules(config.seed, config.width, config.height, config.rules)?;
+
+    while forest.months_elapsed < month {
+        forest.update();
+    }
+
+    let Some(info) = forest.inspect(x, y) else {
+        return Err(format!(
+            "({}, {}) is outside the {}x{} map",
+            x, y, forest.width, forest.height
+        )
+        .into());
+    };
+
+    println!("seed {}", config.seed);
+    draw_info(&forest);
+    draw_cell_info(&info);
+
+    Ok(())
+}
+
+fn parse_position(value: &str) -> Result<(usize, usize), Box<dyn Error>> {
+    match value.split_once(',') {
+        Some((x, y)) => Ok((x.trim().parse()?, y.trim().parse()?)),
+        None => Err(format!("expected a position like 3,4, got {}", value).into()),
+    }
+}
+
+fn draw_cell_info(info: &CellInfo) {
+    let (x, y) = info.position;
+    println!("cell ({}, {}), index {}, raw {:08x}", x, y, info.index, info.cell);
+
+    match &info.tree {
+        Some(tree) => println!(
+            "tree: {}, age {}, species {}, spawn chance {}%, harvest chance {}%, yields {}",
+            tree.kind,
+            tree.age,
+            tree.species,
+            tree.spawn_chance,
+            tree.harvest_chance,
+            tree.harvest_amount
+        ),
+        None => println!("tree: none"),
+    }
+
+    match &info.jack {
+        Some(jack) => println!("jack: level {}, maul chance {}%", jack.level, jack.maul_chance),
+        None => println!("jack: none"),
+    }
+
+    match &info.bear {
+        Some(bear) => match bear.lifecycle {
+            Some(lifecycle) => println!(
+                "bear: {}, age {}, food {}",
+                bear.count, lifecycle.age, lifecycle.food
+            ),
+            None => println!("bear: {}", bear.count),
+        },
+        None => println!("bear: none"),
+    }
+
+    if info.flags != 0 {
+        println!("flags: {:x}", info.flags);
+    }
+
+    let neighbours = &info.neighbours;
+    println!(
+        "neighbours: {} cells, {} trees, {} jacks, {} bears",
+        neighbours.cells, neighbours.trees, neighbours.jacks, neighbours.bears
+    );
+}
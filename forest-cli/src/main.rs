@@ -1,46 +1,107 @@
 use forest_lib::{
-    consts::{BEAR_MASK, JACK_MASK, TREE_MASK},
     forest::Forest,
+    policy::PolicyKind,
+    rules::Rules,
+    stats::YearlyStats,
 };
 use rand::RngCore;
-use std::{env, time::Instant};
+use record::{Record, DEFAULT_CHECKPOINT_EVERY};
+use std::{env, path::PathBuf, process, time::Instant};
+
+mod inspect;
+mod record;
+mod replay;
 
 pub struct ForestConfig {
     pub seed: u64,
     pub width: usize,
     pub height: usize,
     pub months: u32,
+    pub rules: Rules,
+    pub record: Option<PathBuf>,
+    pub checkpoint_every: u32,
+    pub print_hash_every: Option<u32>,
 }
 
 impl ForestConfig {
-    pub fn new(seed: u64, width: usize, height: usize, months: u32) -> Self {
+    pub fn new(seed: u64, width: usize, height: usize, months: u32, rules: Rules) -> Self {
         Self {
             seed,
             width,
             height,
             months,
+            rules,
+            record: None,
+            checkpoint_every: DEFAULT_CHECKPOINT_EVERY,
+            print_hash_every: None,
         }
     }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let start_time = Instant::now();
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
 
+fn run() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "replay") {
+        return replay::run(&args[2..]);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "inspect") {
+        return inspect::run(&args[2..]);
+    }
+
+    let start_time = Instant::now();
     let config = parse_arguments(&args)?;
+    let mut record = config.record.as_ref().map(|_| Record::new(&config));
 
-    let mut forest = Forest::new(config.seed, config.width, config.height);
+    let mut forest = Forest::try_with_rules(
+        config.seed,
+        config.width,
+        config.height,
+        config.rules.clone(),
+    )?;
 
     forest.draw_map();
 
+    if let Some(record) = &mut record {
+        record.observe(&forest);
+    }
+
     while forest.months_elapsed < config.months {
         forest.update();
+
+        if let Some(record) = &mut record {
+            record.observe(&forest);
+        }
+
+        if let Some(every) = config.print_hash_every {
+            if forest.months_elapsed.is_multiple_of(every) {
+                println!(
+                    "month {}: state {:016x}",
+                    forest.months_elapsed,
+                    forest.state_hash()
+                );
+            }
+        }
+    }
+
+    if let (Some(record), Some(path)) = (&record, &config.record) {
+        record.save(path)?;
     }
 
     println!();
 
     forest.draw_map();
-    draw_info(&forest.map, forest.months_elapsed);
+    draw_info(&forest);
+
+    if let Some(stats) = forest.history.last() {
+        draw_yearly_stats(stats, forest.rules.economy);
+    }
 
     let end_time = Instant::now() - start_time;
     println!("Time to run: {:?}", end_time);
@@ -48,13 +109,68 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn parse_arguments(args: &Vec<String>) -> Result<ForestConfig, Box<dyn std::error::Error>> {
+fn parse_arguments(args: &[String]) -> Result<ForestConfig, Box<dyn std::error::Error>> {
     const DEFAULT_WIDTH: usize = 12;
     const DEFAULT_HEIGHT: usize = 8;
     const DEFAULT_MONTHS: u32 = 4800;
 
-    let mut iter = args.iter();
-    let _ = iter.next();
+    let mut rules = Rules::default();
+    let mut policy: Option<PolicyKind> = None;
+    let mut positionals: Vec<&String> = vec![];
+    let mut record: Option<PathBuf> = None;
+    let mut checkpoint_every = DEFAULT_CHECKPOINT_EVERY;
+    let mut print_hash_every: Option<u32> = None;
+
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--bear-lifecycle" => rules.bear_lifecycle = true,
+            "--economy" => rules.economy = true,
+            "--policy" => match args_iter.next() {
+                Some(value) => policy = Some(value.parse()?),
+                None => return Err("missing value for --policy".into()),
+            },
+            "--jack-movement" => match args_iter.next() {
+                Some(value) => rules.jack_movement = value.parse()?,
+                None => return Err("missing value for --jack-movement".into()),
+            },
+            "--record" => match args_iter.next() {
+                Some(value) => record = Some(PathBuf::from(value)),
+                None => return Err("missing value for --record".into()),
+            },
+            "--checkpoint-every" => match args_iter.next() {
+                Some(value) => checkpoint_every = value.parse()?,
+                None => return Err("missing value for --checkpoint-every".into()),
+            },
+            "--print-hash-every" => match args_iter.next() {
+                Some(value) => print_hash_every = Some(value.parse()?),
+                None => return Err("missing value for --print-hash-every".into()),
+            },
+            "--density" => match args_iter.next() {
+                Some(value) => rules.density = value.parse()?,
+                None => return Err("missing value for --density".into()),
+            },
+            "--bear-movement" => match args_iter.next() {
+                Some(value) => rules.bear_movement = value.parse()?,
+                None => return Err("missing value for --bear-movement".into()),
+            },
+            #[cfg(feature = "parallel")]
+            "--tree-threads" => match args_iter.next() {
+                Some(value) => rules.tree_threads = value.parse()?,
+                None => return Err("missing value for --tree-threads".into()),
+            },
+            flag if flag.starts_with("--") => return Err(format!("unknown flag: {}", flag).into()),
+            _ => positionals.push(arg),
+        }
+    }
+
+    rules.policy = match policy {
+        Some(policy) => policy,
+        None if rules.economy => PolicyKind::Profit,
+        None => PolicyKind::Classic,
+    };
+
+    let mut iter = positionals.into_iter();
 
     let seed: u64 = match iter.next() {
         Some(seed) => seed.parse()?,
@@ -72,46 +188,57 @@ fn parse_arguments(args: &Vec<String>) -> Result<ForestConfig, Box<dyn std::erro
         None => DEFAULT_MONTHS,
     };
 
-    Ok(ForestConfig::new(seed, width, height, months))
+    if checkpoint_every == 0 {
+        return Err("--checkpoint-every must be at least 1".into());
+    }
+
+    if print_hash_every == Some(0) {
+        return Err("--print-hash-every must be at least 1".into());
+    }
+
+    let mut config = ForestConfig::new(seed, width, height, months, rules);
+    config.record = record;
+    config.checkpoint_every = checkpoint_every;
+    config.print_hash_every = print_hash_every;
+
+    Ok(config)
 }
 
-fn draw_info(map: &[u16], months_elapsed: u32) {
+fn draw_info(forest: &Forest) {
     println!(
         "{} | {}",
-        get_formatted_time(months_elapsed),
-        get_formatted_entities(map)
+        get_formatted_time(forest.months_elapsed),
+        get_formatted_entities(forest)
     )
 }
 
+fn draw_yearly_stats(stats: &YearlyStats, economy: bool) {
+    println!(
+        "last year: lumber {}, mauls {}, hired {}, fired {}",
+        stats.lumber, stats.mauls, stats.jacks_hired, stats.jacks_fired
+    );
+
+    if !economy {
+        return;
+    }
+
+    println!(
+        "economy: stockpile {}, sold {}, revenue {}, wages {}, profit {}, funds {}",
+        stats.stockpile, stats.lumber_sold, stats.revenue, stats.wages, stats.profit, stats.funds
+    );
+}
+
 fn get_formatted_time(months_elapsed: u32) -> String {
     let years: u32 = months_elapsed / 12;
     let months: u32 = months_elapsed % 12;
     format!("year {}, month {}", years, months)
 }
 
-fn get_formatted_entities(map: &[u16]) -> String {
-    let mut num_bears = 0;
-    let mut num_jacks = 0;
-    let mut num_trees = 0;
-
-    for i in 0..map.len() {
-        let cell = map[i];
-
-        if (cell & BEAR_MASK) > 0 {
-            num_bears += 1;
-        }
-
-        if (cell & JACK_MASK) > 0 {
-            num_jacks += 1;
-        }
-
-        if (cell & TREE_MASK) > 0 {
-            num_trees += 1;
-        }
-    }
-
+fn get_formatted_entities(forest: &Forest) -> String {
     format!(
         "bears {}, jacks {}, trees {}",
-        num_bears, num_jacks, num_trees
+        forest.bear_count(),
+        forest.jack_count(),
+        forest.tree_count()
     )
 }
@@ -0,0 +1,268 @@
+use forest_lib::{error::ForestError, forest::Forest, rules::Rules, stats::YearlyStats};
+use std::{collections::BTreeMap, error::Error, fmt::Write as _, fs, path::Path};
+
+use crate::ForestConfig;
+
+const HEADER: &str = "forest-record 1";
+pub const DEFAULT_CHECKPOINT_EVERY: u32 = 12;
+
+/// A recorded run: everything needed to rebuild it, plus the yearly events it produced
+/// and the map hash at every checkpoint so a replay can tell when it has diverged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub seed: u64,
+    pub width: usize,
+    pub height: usize,
+    pub months: u32,
+    pub checkpoint_every: u32,
+    pub rules: Rules,
+    pub events: BTreeMap<u32, String>,
+    pub checkpoints: BTreeMap<u32, u64>,
+}
+
+impl Record {
+    pub fn new(config: &ForestConfig) -> Self {
+        Self {
+            seed: config.seed,
+            width: config.width,
+            height: config.height,
+            months: config.months,
+            checkpoint_every: config.checkpoint_every,
+            rules: config.rules.clone(),
+            events: BTreeMap::new(),
+            checkpoints: BTreeMap::new(),
+        }
+    }
+
+    pub fn build_forest(&self) -> Result<Forest, ForestError> {
+        Forest::try_with_rules(self.seed, self.width, self.height, self.rules.clone())
+    }
+
+    pub fn is_checkpoint(&self, month: u32) -> bool {
+        month.is_multiple_of(self.checkpoint_every)
+    }
+
+    /// Notes the forest's latest yearly event and, on checkpoint months, its map hash.
+    pub fn observe(&mut self, forest: &Forest) {
+        if let Some(stats) = forest.history.last() {
+            self.events
+                .entry(stats.year)
+                .or_insert_with(|| describe_year(stats));
+        }
+
+        if self.is_checkpoint(forest.months_elapsed) {
+            self.checkpoints
+                .insert(forest.months_elapsed, forest.map_hash());
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut out = String::new();
+        writeln!(out, "{}", HEADER)?;
+        writeln!(out, "seed {}", self.seed)?;
+        writeln!(out, "size {} {}", self.width, self.height)?;
+        writeln!(out, "months {}", self.months)?;
+        writeln!(out, "checkpoint-every {}", self.checkpoint_every)?;
+
+        for (key, value) in rule_entries(&self.rules) {
+            writeln!(out, "rule {} {}", key, value)?;
+        }
+
+        for (year, event) in &self.events {
+            writeln!(out, "year {} {}", year, event)?;
+        }
+
+        for (month, hash) in &self.checkpoints {
+            writeln!(out, "checkpoint {} {:016x}", month, hash)?;
+        }
+
+        fs::write(path, out)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+
+        if lines.next() != Some(HEADER) {
+            return Err(format!("{} is not a forest record", path.display()).into());
+        }
+
+        let mut record = Record {
+            seed: 0,
+            width: 0,
+            height: 0,
+            months: 0,
+            checkpoint_every: DEFAULT_CHECKPOINT_EVERY,
+            rules: Rules::default(),
+            events: BTreeMap::new(),
+            checkpoints: BTreeMap::new(),
+        };
+
+        for (number, line) in lines.enumerate() {
+            let invalid = || format!("invalid record line {}: {}", number + 2, line);
+            let mut parts = line.splitn(3, ' ');
+
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some("seed"), Some(seed), None) => record.seed = seed.parse()?,
+                (Some("size"), Some(width), Some(height)) => {
+                    record.width = width.parse()?;
+                    record.height = height.parse()?;
+                }
+                (Some("months"), Some(months), None) => record.months = months.parse()?,
+                (Some("checkpoint-every"), Some(every), None) => {
+                    record.checkpoint_every = every.parse()?
+                }
+                (Some("rule"), Some(key), Some(value)) => {
+                    apply_rule(&mut record.rules, key, value)
+                        .map_err(|error| format!("{} ({})", invalid(), error))?
+                }
+                (Some("year"), Some(year), Some(event)) => {
+                    record.events.insert(year.parse()?, event.to_string());
+                }
+                (Some("checkpoint"), Some(month), Some(hash)) => {
+                    let hash = u64::from_str_radix(hash, 16).map_err(|_| invalid())?;
+                    record.checkpoints.insert(month.parse()?, hash);
+                }
+                (Some(""), None, None) => {}
+                _ => return Err(invalid().into()),
+            }
+        }
+
+        if record.checkpoint_every == 0 {
+            return Err("checkpoint interval must be at least 1".into());
+        }
+
+        Ok(record)
+    }
+}
+
+pub fn describe_year(stats: &YearlyStats) -> String {
+    format!(
+        "trees {} jacks {} bears {} lumber {} mauls {} hired {} fired {}",
+        stats.trees,
+        stats.jacks,
+        stats.bears,
+        stats.lumber,
+        stats.mauls,
+        stats.jacks_hired,
+        stats.jacks_fired
+    )
+}
+
+fn rule_entries(rules: &Rules) -> Vec<(&'static str, String)> {
+    vec![
+        ("bear-lifecycle", rules.bear_lifecycle.to_string()),
+        ("economy", rules.economy.to_string()),
+        ("policy", rules.policy.to_string()),
+        ("jack-movement", rules.jack_movement.to_string()),
+        ("bear-movement", rules.bear_movement.to_string()),
+        ("density", rules.density.to_string()),
+        ("tree-threads", rules.tree_threads.to_string()),
+    ]
+}
+
+fn apply_rule(rules: &mut Rules, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+    match key {
+        "bear-lifecycle" => rules.bear_lifecycle = value.parse()?,
+        "economy" => rules.economy = value.parse()?,
+        "policy" => rules.policy = value.parse()?,
+        "jack-movement" => rules.jack_movement = value.parse()?,
+        "bear-movement" => rules.bear_movement = value.parse()?,
+        "density" => rules.density = value.parse()?,
+        "tree-threads" => {
+            rules.tree_threads = value.parse()?;
+            if cfg!(not(feature = "parallel")) && rules.tree_threads > 1 {
+                return Err("record needs the parallel feature".into());
+            }
+        }
+        _ => return Err(format!("unknown rule: {}", key).into()),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forest_lib::{
+        movement::{BearMovement, JackMovement},
+        policy::PolicyKind,
+        rules::Density,
+    };
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("forest-{}-{}.rec", name, std::process::id()))
+    }
+
+    fn recorded(rules: Rules, months: u32) -> Record {
+        let config = ForestConfig::new(42, 20, 15, months, rules);
+        let mut record = Record::new(&config);
+        let mut forest = record.build_forest().unwrap();
+
+        record.observe(&forest);
+        while forest.months_elapsed < months {
+            forest.update();
+            record.observe(&forest);
+        }
+
+        record
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let rules = Rules {
+            bear_lifecycle: true,
+            economy: true,
+            policy: PolicyKind::FixedQuota { jacks: 8, bears: 2 },
+            jack_movement: JackMovement::Greedy { sight: 5 },
+            bear_movement: BearMovement::Hunt { sight: 3, chase: 4 },
+            density: Density { trees: 0.4, jacks: 0.05, bears: 0.01 },
+            tree_threads: 1,
+        };
+        let record = recorded(rules, 60);
+        assert_eq!(record.checkpoints.len(), 6);
+        assert_eq!(record.events.len(), 5);
+
+        let path = temp_path("round-trip");
+        record.save(&path).unwrap();
+        let loaded = Record::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap(), record);
+    }
+
+    #[test]
+    fn load_rejects_malformed_records() {
+        let path = temp_path("malformed");
+        for contents in [
+            "not a record\n",
+            "forest-record 1\nseed twelve\n",
+            "forest-record 1\ncheckpoint 12 not-hex\n",
+            "forest-record 1\nrule gravity on\n",
+            "forest-record 1\ncheckpoint-every 0\n",
+        ] {
+            fs::write(&path, contents).unwrap();
+            assert!(Record::load(&path).is_err(), "{:?}", contents);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn tree_threads_need_the_parallel_feature() {
+        let mut rules = Rules::default();
+        apply_rule(&mut rules, "tree-threads", "1").unwrap();
+        assert_eq!(rules.tree_threads, 1);
+
+        let result = apply_rule(&mut rules, "tree-threads", "4");
+        if cfg!(feature = "parallel") {
+            assert!(result.is_ok());
+            assert_eq!(rules.tree_threads, 4);
+        } else {
+            assert!(result.is_err());
+        }
+
+        assert!(apply_rule(&mut rules, "tree-threads", "-1").is_err());
+    }
+}
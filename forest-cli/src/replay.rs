@@ -0,0 +1,320 @@
+use forest_lib::{error::ForestError, forest::Forest};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+use crate::draw_info;
+use crate::record::{describe_year, Record};
+
+pub enum Divergence {
+    Checkpoint {
+        month: u32,
+        expected: u64,
+        actual: u64,
+    },
+    Event {
+        year: u32,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Divergence::Checkpoint {
+                month,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "month {}: map hash {:016x}, recorded {:016x}",
+                month, actual, expected
+            ),
+            Divergence::Event {
+                year,
+                expected,
+                actual,
+            } => write!(f, "year {}: {}, recorded {}", year, actual, expected),
+        }
+    }
+}
+
+/// Steps through a recorded run, keeping a snapshot of the forest at every checkpoint so
+/// that seeking backwards only replays the months since the nearest one.
+pub struct Replay {
+    record: Record,
+    snapshots: BTreeMap<u32, Forest>,
+    forest: Forest,
+}
+
+impl Replay {
+    pub fn new(record: Record) -> Result<Self, ForestError> {
+        let forest = record.build_forest()?;
+        let snapshots = BTreeMap::from([(0, forest.clone())]);
+
+        Ok(Self {
+            record,
+            snapshots,
+            forest,
+        })
+    }
+
+    /// Replays the whole recording once, comparing every yearly event and checkpoint hash
+    /// against what was recorded.
+    pub fn verify(&mut self) -> Vec<Divergence> {
+        let mut divergences = vec![];
+        let mut forest = self.snapshots[&0].clone();
+        self.check_checkpoint(&forest, &mut divergences);
+
+        while forest.months_elapsed < self.record.months {
+            forest.update();
+
+            if forest.months_elapsed.is_multiple_of(12) {
+                if let (Some(stats), Some(expected)) = (
+                    forest.history.last(),
+                    self.record.events.get(&(forest.months_elapsed / 12)),
+                ) {
+                    let actual = describe_year(stats);
+                    if actual != *expected {
+                        divergences.push(Divergence::Event {
+                            year: stats.year,
+                            expected: expected.clone(),
+                            actual,
+                        });
+                    }
+                }
+            }
+
+            if self.record.is_checkpoint(forest.months_elapsed) {
+                self.check_checkpoint(&forest, &mut divergences);
+                self.snapshots.insert(forest.months_elapsed, forest.clone());
+            }
+        }
+
+        divergences
+    }
+
+    fn check_checkpoint(&self, forest: &Forest, divergences: &mut Vec<Divergence>) {
+        let month = forest.months_elapsed;
+        if let Some(&expected) = self.record.checkpoints.get(&month) {
+            let actual = forest.map_hash();
+            if actual != expected {
+                divergences.push(Divergence::Checkpoint {
+                    month,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+
+    /// Moves to `month`, or to the end of the recording if it is further than that.
+    pub fn goto(&mut self, month: u32) {
+        let month = month.min(self.record.months);
+        if month < self.forest.months_elapsed {
+            let (_, snapshot) = self
+                .snapshots
+                .range(..=month)
+                .next_back()
+                .expect("replay always has a snapshot of month 0");
+            self.forest = snapshot.clone();
+        }
+
+        while self.forest.months_elapsed < month {
+            self.forest.update();
+        }
+    }
+
+    pub fn forest(&self) -> &Forest {
+        &self.forest
+    }
+
+    fn describe_position(&self) -> String {
+        let month = self.forest.months_elapsed;
+        let hash = self.forest.map_hash();
+
+        let status = match self.record.checkpoints.get(&month) {
+            Some(&expected) if expected == hash => " (matches checkpoint)",
+            Some(_) => " (DIVERGED from checkpoint)",
+            None => "",
+        };
+
+        format!("hash {:016x}{}", hash, status)
+    }
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut path = None;
+    let mut verify_only = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--verify-only" => verify_only = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown flag: {}", flag).into()),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument: {}", arg).into()),
+        }
+    }
+
+    let Some(path) = path else {
+        return Err("usage: forest-cli replay <record> [--verify-only]".into());
+    };
+
+    let record = Record::load(Path::new(path))?;
+    let mut replay = Replay::new(record)?;
+    let divergences = replay.verify();
+
+    if divergences.is_empty() {
+        println!(
+            "replayed {} months, all {} checkpoints match",
+            replay.record.months,
+            replay.record.checkpoints.len()
+        );
+    } else {
+        for divergence in &divergences {
+            println!("divergence at {}", divergence);
+        }
+    }
+
+    if verify_only {
+        return match divergences.len() {
+            0 => Ok(()),
+            count => Err(format!("replay diverged from the record in {} places", count).into()),
+        };
+    }
+
+    interact(&mut replay)
+}
+
+enum Command {
+    Goto(u32),
+    Map,
+    Quit,
+    Nothing,
+}
+
+fn parse_command(line: &str, current: u32) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let command = words.next();
+    let amount = match words.next() {
+        Some(amount) => Some(
+            amount
+                .parse::<u32>()
+                .map_err(|_| format!("not a number of months: {}", amount))?,
+        ),
+        None => None,
+    };
+
+    match (command, amount) {
+        (Some("goto" | "g"), Some(month)) => Ok(Command::Goto(month)),
+        (Some("goto" | "g"), None) => Err("goto needs a month".to_string()),
+        (Some("next" | "n"), amount) => Ok(Command::Goto(current.saturating_add(amount.unwrap_or(1)))),
+        (Some("prev" | "p"), amount) => Ok(Command::Goto(current.saturating_sub(amount.unwrap_or(1)))),
+        (Some("map" | "m"), None) => Ok(Command::Map),
+        (Some("quit" | "q"), None) => Ok(Command::Quit),
+        (None, _) => Ok(Command::Nothing),
+        (Some(command), _) => Err(format!("unknown command: {}", command)),
+    }
+}
+
+fn interact(replay: &mut Replay) -> Result<(), Box<dyn Error>> {
+    println!("commands: goto <month>, next [months], prev [months], map, quit");
+    draw_position(replay);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        match parse_command(&line, replay.forest().months_elapsed) {
+            Ok(Command::Goto(month)) => {
+                replay.goto(month);
+                draw_position(replay);
+            }
+            Ok(Command::Map) => replay.forest().draw_map(),
+            Ok(Command::Quit) => return Ok(()),
+            Ok(Command::Nothing) => {}
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+fn draw_position(replay: &Replay) {
+    draw_info(replay.forest());
+    println!("{}", replay.describe_position());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ForestConfig;
+    use forest_lib::rules::Rules;
+
+    fn replay_of(months: u32, tamper: impl FnOnce(&mut Record)) -> Replay {
+        let config = ForestConfig::new(7, 20, 15, months, Rules::default());
+        let mut record = Record::new(&config);
+        let mut forest = record.build_forest().unwrap();
+
+        record.observe(&forest);
+        while forest.months_elapsed < months {
+            forest.update();
+            record.observe(&forest);
+        }
+
+        tamper(&mut record);
+        Replay::new(record).unwrap()
+    }
+
+    #[test]
+    fn faithful_record_verifies() {
+        let mut replay = replay_of(48, |_| {});
+        assert!(replay.verify().is_empty());
+        assert_eq!(replay.snapshots.len(), 5);
+    }
+
+    #[test]
+    fn tampered_checkpoint_and_event_are_reported() {
+        let mut replay = replay_of(48, |record| {
+            *record.checkpoints.get_mut(&24).unwrap() ^= 1;
+            record.events.insert(3, "tampered".to_string());
+        });
+
+        let divergences = replay.verify();
+        assert_eq!(divergences.len(), 2);
+        assert!(matches!(
+            divergences[0],
+            Divergence::Checkpoint { month: 24, .. }
+        ));
+        assert!(matches!(
+            &divergences[1],
+            Divergence::Event { year: 3, expected, .. } if expected == "tampered"
+        ));
+    }
+
+    #[test]
+    fn goto_stays_within_the_recording() {
+        let mut replay = replay_of(36, |_| {});
+        replay.verify();
+
+        replay.goto(u32::MAX);
+        assert_eq!(replay.forest().months_elapsed, 36);
+
+        replay.goto(13);
+        assert_eq!(replay.forest().months_elapsed, 13);
+        assert_eq!(replay.forest().map_hash(), {
+            let mut forest = replay.snapshots[&0].clone();
+            (0..13).for_each(|_| forest.update());
+            forest.map_hash()
+        });
+    }
+}
@@ -5,79 +5,293 @@
     windows_subsystem = "windows"
 )]
 
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
-use forest_lib::forest::Forest;
-use serde::Serialize;
+use forest_lib::{forest::Forest, rules::Rules, stats::YearlyStats};
+use forest_session::{
+    details::{self, CellDetails},
+    editing::{PaintBrush, PaintEdit},
+    registry::{Entry, ForestId, Registry},
+    rules::RuleSettings,
+    session::{ForestFrame, ForestInfo, ForestSummary},
+    statistics::{self, YearRange},
+    stop::StopCondition,
+    DEFAULT_HEIGHT, DEFAULT_SEED, DEFAULT_WIDTH, MAX_STEP_MONTHS,
+};
+use tauri::Manager;
 
-const DEFAULT_SEED: u64 = 123123;
-const DEFAULT_WIDTH: usize = 120;
-const DEFAULT_HEIGHT: usize = 80;
+const FRAME_EVENT: &str = "forest-frame";
 
 struct AppState {
-    forest: Mutex<Forest>,
+    forests: Mutex<Registry>,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
-            forest: Mutex::new(Forest::new(
-                DEFAULT_SEED,
-                DEFAULT_WIDTH,
-                DEFAULT_HEIGHT,
-            )),
-        }
+impl AppState {
+    fn entry(&self, id: ForestId) -> Result<Entry, String> {
+        self.forests.lock().unwrap().get(id)
     }
 }
 
-#[derive(Serialize)]
-struct ForestInfo {
-    map: Vec<u16>,
-    width: usize,
-    height: usize,
-    months_elapsed: u32,
-    yearly_lumber: u32,
-    yearly_mauls: u32,
-}
+impl Default for AppState {
+    fn default() -> Self {
+        let mut forests = Registry::default();
+        forests.insert(None, Forest::new(DEFAULT_SEED, DEFAULT_WIDTH, DEFAULT_HEIGHT));
 
-impl ForestInfo {
-    fn new(forest: &Forest) -> Self {
         Self {
-            map: forest.map.clone(),
-            width: forest.width,
-            height: forest.height,
-            months_elapsed: forest.months_elapsed,
-            yearly_lumber: forest.yearly_lumber,
-            yearly_mauls: forest.yearly_mauls,
+            forests: Mutex::new(forests),
         }
     }
 }
 
+/// Builds a forest from the GUI's settings. With an `id` it replaces that forest, which
+/// can be undone; otherwise it is opened alongside the others under a new id.
 #[tauri::command]
 fn create_forest(
+    id: Option<ForestId>,
+    name: Option<String>,
     seed: Option<u64>,
     width: Option<usize>,
     height: Option<usize>,
+    rules: Option<RuleSettings>,
     state: tauri::State<AppState>
-) -> ForestInfo {
-    let forest = Forest::new(
+) -> Result<ForestInfo, String> {
+    let rules = match rules {
+        Some(rules) => rules.into_rules(Rules::default())?,
+        None => Rules::default(),
+    };
+
+    let forest = Forest::try_with_rules(
         seed.unwrap_or(DEFAULT_SEED),
         width.unwrap_or(DEFAULT_WIDTH),
         height.unwrap_or(DEFAULT_HEIGHT),
-    );
-    *state.forest.lock().unwrap() = forest;
-    ForestInfo::new(&state.forest.lock().unwrap())
+        rules,
+    )
+    .map_err(|err| format!("{}", err))?;
+
+    match id {
+        Some(id) => {
+            let entry = state.entry(id)?;
+            let mut session = entry.session.lock().unwrap();
+            if let Some(name) = name {
+                session.name = name;
+            }
+            Ok(session.replace(forest))
+        }
+        None => {
+            let entry = state.forests.lock().unwrap().insert(name, forest);
+            let mut session = entry.session.lock().unwrap();
+            Ok(session.resync())
+        }
+    }
+}
+
+/// Closes a forest, stopping its simulation if it is running.
+#[tauri::command]
+fn remove_forest(id: ForestId, state: tauri::State<AppState>) -> Result<(), String> {
+    state.forests.lock().unwrap().remove(id)
+}
+
+/// Summaries of every open forest in id order, for comparing scenarios.
+#[tauri::command]
+fn list_forests(state: tauri::State<AppState>) -> Vec<ForestSummary> {
+    let entries: Vec<Entry> = state.forests.lock().unwrap().entries().cloned().collect();
+    entries
+        .iter()
+        .map(|entry| entry.session.lock().unwrap().summary())
+        .collect()
+}
+
+#[tauri::command]
+fn get_forest(id: ForestId, state: tauri::State<AppState>) -> Result<ForestInfo, String> {
+    Ok(state.entry(id)?.session.lock().unwrap().snapshot())
+}
+
+/// Returns the whole map and restarts the frame sequence from it, for a GUI that has
+/// missed a frame.
+#[tauri::command]
+fn resync_forest(id: ForestId, state: tauri::State<AppState>) -> Result<ForestInfo, String> {
+    Ok(state.entry(id)?.session.lock().unwrap().resync())
+}
+
+/// Describes the cell at `(x, y)` for the map's popup.
+#[tauri::command]
+fn inspect_cell(
+    id: ForestId,
+    x: usize,
+    y: usize,
+    state: tauri::State<AppState>
+) -> Result<CellDetails, String> {
+    let entry = state.entry(id)?;
+    let session = entry.session.lock().unwrap();
+    match session.forest.inspect(x, y) {
+        Some(info) => Ok(details::cell_details(&info)),
+        None => Err(format!("({}, {}) is outside the map", x, y)),
+    }
+}
+
+/// Applies `edit` to the cells under `brush` and returns a frame of what changed.
+#[tauri::command]
+fn paint_cells(
+    id: ForestId,
+    brush: PaintBrush,
+    edit: PaintEdit,
+    state: tauri::State<AppState>
+) -> Result<ForestFrame, String> {
+    let entry = state.entry(id)?;
+    let mut session = entry.session.lock().unwrap();
+    session
+        .change(|forest| forest.paint(brush.into(), edit.into()))
+        .map_err(|err| format!("{}", err))?;
+
+    Ok(session.frame())
+}
+
+#[tauri::command]
+fn get_rules(id: ForestId, state: tauri::State<AppState>) -> Result<RuleSettings, String> {
+    let entry = state.entry(id)?;
+    let session = entry.session.lock().unwrap();
+    Ok(RuleSettings::from(&session.forest.rules))
+}
+
+/// Changes the rules the forest runs under from its next update, which can be undone.
+/// The density only applies to forests created afterwards.
+#[tauri::command]
+fn set_rules(
+    id: ForestId,
+    rules: RuleSettings,
+    state: tauri::State<AppState>
+) -> Result<ForestFrame, String> {
+    let entry = state.entry(id)?;
+    let mut session = entry.session.lock().unwrap();
+    let rules = rules.into_rules(session.forest.rules.clone())?;
+    session
+        .change(|forest| forest.set_rules(rules))
+        .map_err(|err| format!("{}", err))?;
+
+    Ok(session.frame())
+}
+
+/// The yearly statistics recorded so far, limited to `range` if given.
+#[tauri::command]
+fn get_statistics(
+    id: ForestId,
+    range: Option<YearRange>,
+    state: tauri::State<AppState>
+) -> Result<Vec<YearlyStats>, String> {
+    let entry = state.entry(id)?;
+    let session = entry.session.lock().unwrap();
+    Ok(statistics::yearly_statistics(
+        &session.forest.history,
+        &range.unwrap_or_default(),
+    ))
+}
+
+#[tauri::command]
+fn update_forest(id: ForestId, state: tauri::State<AppState>) -> Result<(), String> {
+    let entry = state.entry(id)?;
+    let mut session = entry.session.lock().unwrap();
+    session.record();
+    session.forest.update();
+
+    Ok(())
+}
+
+/// Advances the forest `months` months under a single lock. At most `MAX_STEP_MONTHS`
+/// months can be asked for at once.
+#[tauri::command(async)]
+fn step_forest(
+    id: ForestId,
+    months: u32,
+    state: tauri::State<AppState>
+) -> Result<ForestFrame, String> {
+    if months > MAX_STEP_MONTHS {
+        return Err(format!("cannot step more than {} months at once", MAX_STEP_MONTHS));
+    }
+
+    let entry = state.entry(id)?;
+    let mut session = entry.session.lock().unwrap();
+    session.record();
+    for _ in 0..months {
+        session.forest.update();
+    }
+
+    Ok(session.frame())
+}
+
+/// Advances the forest until `condition` holds, giving up after `max_months` months
+/// (`MAX_STEP_MONTHS` if not given, and at most that) so a condition that is never met
+/// cannot hang the app.
+#[tauri::command(async)]
+fn run_until(
+    id: ForestId,
+    condition: StopCondition,
+    max_months: Option<u32>,
+    state: tauri::State<AppState>
+) -> Result<ForestFrame, String> {
+    let max_months = max_months.unwrap_or(MAX_STEP_MONTHS);
+    if max_months > MAX_STEP_MONTHS {
+        return Err(format!("cannot run more than {} months at once", MAX_STEP_MONTHS));
+    }
+
+    let entry = state.entry(id)?;
+    let mut session = entry.session.lock().unwrap();
+    session.record();
+    let limit = session.forest.months_elapsed.saturating_add(max_months);
+
+    while !condition.is_met(&session.forest) && session.forest.months_elapsed < limit {
+        session.forest.update();
+    }
+
+    Ok(session.frame())
+}
+
+#[tauri::command]
+fn undo(id: ForestId, state: tauri::State<AppState>) -> Result<ForestInfo, String> {
+    state.entry(id)?.session.lock().unwrap().undo()
+}
+
+#[tauri::command]
+fn redo(id: ForestId, state: tauri::State<AppState>) -> Result<ForestInfo, String> {
+    state.entry(id)?.session.lock().unwrap().redo()
+}
+
+/// Returns the forest to how it was at `month`, starting from the latest kept snapshot
+/// at or before it.
+#[tauri::command(async)]
+fn rewind_to(id: ForestId, month: u32, state: tauri::State<AppState>) -> Result<ForestInfo, String> {
+    state.entry(id)?.session.lock().unwrap().rewind_to(month)
+}
+
+/// Starts advancing the forest on a background thread at `speed` months per second,
+/// emitting a `forest-frame` event with a `ForestFrame` of the changed cells after each frame.
+/// Each forest has its own thread, and frames carry the forest's id.
+#[tauri::command]
+fn start_simulation(
+    id: ForestId,
+    speed: u32,
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>
+) -> Result<(), String> {
+    let entry = state.entry(id)?;
+    entry.playback.start(speed, Arc::clone(&entry.session), move |frame| {
+        app.emit_all(FRAME_EVENT, frame).is_ok()
+    })
+}
+
+#[tauri::command]
+fn pause_simulation(id: ForestId, state: tauri::State<AppState>) -> Result<(), String> {
+    state.entry(id)?.playback.pause();
+    Ok(())
 }
 
 #[tauri::command]
-fn get_forest(state: tauri::State<AppState>) -> ForestInfo {
-    ForestInfo::new(&state.forest.lock().unwrap())
+fn set_speed(id: ForestId, speed: u32, state: tauri::State<AppState>) -> Result<(), String> {
+    state.entry(id)?.playback.set_speed(speed)
 }
 
 #[tauri::command]
-fn update_forest(state: tauri::State<AppState>) {
-    let mut forest = state.forest.lock().unwrap();
-    forest.update();
+fn is_simulation_running(id: ForestId, state: tauri::State<AppState>) -> Result<bool, String> {
+    Ok(state.entry(id)?.playback.is_running())
 }
 
 fn main() {
@@ -85,8 +299,25 @@ fn main() {
         .manage(AppState::default())
         .invoke_handler(tauri::generate_handler![
             create_forest,
+            remove_forest,
+            list_forests,
             get_forest,
+            resync_forest,
+            inspect_cell,
+            paint_cells,
+            get_rules,
+            set_rules,
+            get_statistics,
+            undo,
+            redo,
+            rewind_to,
             update_forest,
+            step_forest,
+            run_until,
+            start_simulation,
+            pause_simulation,
+            set_speed,
+            is_simulation_running,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
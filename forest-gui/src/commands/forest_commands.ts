@@ -1,11 +1,15 @@
 import { invoke } from "@tauri-apps/api";
 import { InvokeArgs } from "@tauri-apps/api/tauri";
-import { IForestInfo } from "../types/response";
+import { ICellDetails, IForestFrame, IForestInfo, IForestSummary, IRules, IYearlyStats } from "../types/response";
 
 export interface ICreateForestArgs extends InvokeArgs {
+  // Replaces this forest when given, otherwise opens a new one.
+  id?: number;
+  name?: string;
   seed?: number;
   width?: number;
   height?: number;
+  rules?: IRules;
 }
 
 export async function create_forest(args: ICreateForestArgs): Promise<IForestInfo> {
@@ -16,17 +20,179 @@ export async function create_forest(args: ICreateForestArgs): Promise<IForestInf
   }
 }
 
-export async function get_forest(): Promise<IForestInfo> {
+export async function remove_forest(id: number): Promise<void> {
   try {
-    return await invoke<IForestInfo>('get_forest');
+    return await invoke<void>('remove_forest', { id });
   } catch (err) {
     throw err;
   }
 }
 
-export async function update_forest(): Promise<void> {
+export async function list_forests(): Promise<IForestSummary[]> {
   try {
-    return await invoke<void>('update_forest');
+    return await invoke<IForestSummary[]>('list_forests');
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function get_forest(id: number): Promise<IForestInfo> {
+  try {
+    return await invoke<IForestInfo>('get_forest', { id });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export type StopCondition =
+  | { kind: 'month', month: number }
+  | { kind: 'no_bears' }
+  | { kind: 'no_jacks' }
+  | { kind: 'no_trees' };
+
+export async function resync_forest(id: number): Promise<IForestInfo> {
+  try {
+    return await invoke<IForestInfo>('resync_forest', { id });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function step_forest(id: number, months: number): Promise<IForestFrame> {
+  try {
+    return await invoke<IForestFrame>('step_forest', { id, months });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function run_until(id: number, condition: StopCondition, max_months?: number): Promise<IForestFrame> {
+  try {
+    // Tauri matches command arguments by their camelCase names.
+    return await invoke<IForestFrame>('run_until', { id, condition, maxMonths: max_months });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function start_simulation(id: number, speed: number): Promise<void> {
+  try {
+    return await invoke<void>('start_simulation', { id, speed });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function pause_simulation(id: number): Promise<void> {
+  try {
+    return await invoke<void>('pause_simulation', { id });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function set_speed(id: number, speed: number): Promise<void> {
+  try {
+    return await invoke<void>('set_speed', { id, speed });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function is_simulation_running(id: number): Promise<boolean> {
+  try {
+    return await invoke<boolean>('is_simulation_running', { id });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export type PaintBrush =
+  | { kind: 'cell', x: number, y: number }
+  | { kind: 'rect', x: number, y: number, width: number, height: number }
+  | { kind: 'circle', x: number, y: number, radius: number };
+
+export type PaintEdit =
+  | { kind: 'set_tree', age: number, species?: number }
+  | { kind: 'clear_tree' }
+  | { kind: 'place_jack', level: number }
+  | { kind: 'remove_jack' }
+  | { kind: 'place_bear' }
+  | { kind: 'remove_bear' };
+
+export async function paint_cells(id: number, brush: PaintBrush, edit: PaintEdit): Promise<IForestFrame> {
+  try {
+    return await invoke<IForestFrame>('paint_cells', { id, brush, edit });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function inspect_cell(id: number, x: number, y: number): Promise<ICellDetails> {
+  try {
+    return await invoke<ICellDetails>('inspect_cell', { id, x, y });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function get_rules(id: number): Promise<IRules> {
+  try {
+    return await invoke<IRules>('get_rules', { id });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function set_rules(id: number, rules: IRules): Promise<IForestFrame> {
+  try {
+    return await invoke<IForestFrame>('set_rules', { id, rules });
+  } catch (err) {
+    throw err;
+  }
+}
+
+// Years from start up to but not including end; either may be left out.
+export interface IYearRange {
+  start?: number;
+  end?: number;
+}
+
+export async function get_statistics(id: number, range?: IYearRange): Promise<IYearlyStats[]> {
+  try {
+    return await invoke<IYearlyStats[]>('get_statistics', { id, range });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function undo(id: number): Promise<IForestInfo> {
+  try {
+    return await invoke<IForestInfo>('undo', { id });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function redo(id: number): Promise<IForestInfo> {
+  try {
+    return await invoke<IForestInfo>('redo', { id });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function rewind_to(id: number, month: number): Promise<IForestInfo> {
+  try {
+    return await invoke<IForestInfo>('rewind_to', { id, month });
+  } catch (err) {
+    throw err;
+  }
+}
+
+export async function update_forest(id: number): Promise<void> {
+  try {
+    return await invoke<void>('update_forest', { id });
   } catch (err) {
     throw err;
   }
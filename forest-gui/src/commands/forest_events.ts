@@ -0,0 +1,8 @@
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { IForestFrame } from "../types/response";
+
+export const FOREST_FRAME_EVENT: string = 'forest-frame';
+
+export async function on_forest_frame(callback: (frame: IForestFrame) => void): Promise<UnlistenFn> {
+  return await listen<IForestFrame>(FOREST_FRAME_EVENT, event => callback(event.payload));
+}
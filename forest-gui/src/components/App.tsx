@@ -1,43 +1,106 @@
 import { info } from 'console';
-import { ReactElement, useEffect, useState } from 'react';
-import { create_forest, get_forest, update_forest } from '../commands/forest_commands';
-import { IForestInfo } from '../types/response';
+import { ReactElement, useEffect, useRef, useState } from 'react';
+import {
+  create_forest,
+  get_forest,
+  get_rules,
+  get_statistics,
+  is_simulation_running,
+  list_forests,
+  pause_simulation,
+  redo,
+  remove_forest,
+  resync_forest,
+  rewind_to,
+  run_until,
+  set_rules,
+  set_speed,
+  start_simulation,
+  step_forest,
+  PaintEdit,
+  StopCondition,
+  undo,
+} from '../commands/forest_commands';
+import { on_forest_frame } from '../commands/forest_events';
+import { IForestFrame, IForestInfo, IForestSummary, IRules, IYearlyStats } from '../types/response';
 import './App.css';
+import Chart from './Chart';
 import Map from './Map';
 
 const DEFAULT_SEED: number = 123123;
 const DEFAULT_WIDTH: number = 120;
 const DEFAULT_HEIGHT: number = 80;
 const DEFAULT_CELL_DRAW_SIZE: number = 8;
-
-export function get_bear_amount(forestInfo: IForestInfo): number {
-  return forestInfo.map.reduce((acc, cell) => {
-    if ((cell & 0xf000) > 0) {
-      return acc + 1;
-    }
-
-    return acc;
-  }, 0);
-}
-
-export function get_jack_amount(forestInfo: IForestInfo): number {
-  return forestInfo.map.reduce((acc, cell) => {
-    if ((cell & 0x0f00) > 0) {
-      return acc + 1;
-    }
-
-    return acc;
-  }, 0);
+const DEFAULT_STEP_MONTHS: number = 1;
+const DEFAULT_SPEED: number = 1;
+const STOP_CONDITIONS: { [name: string]: StopCondition } = {
+  'no bears': { kind: 'no_bears' },
+  'no jacks': { kind: 'no_jacks' },
+  'no trees': { kind: 'no_trees' },
+};
+const DEFAULT_STOP_CONDITION: string = 'no bears';
+const PAINT_TOOLS: { [name: string]: PaintEdit | null } = {
+  'inspect': null,
+  'sapling': { kind: 'set_tree', age: 1 },
+  'mature tree': { kind: 'set_tree', age: 12 },
+  'elder tree': { kind: 'set_tree', age: 120 },
+  'jack': { kind: 'place_jack', level: 1 },
+  'bear': { kind: 'place_bear' },
+  'erase trees': { kind: 'clear_tree' },
+  'erase jacks': { kind: 'remove_jack' },
+  'erase bears': { kind: 'remove_bear' },
+};
+const DEFAULT_PAINT_TOOL: string = 'inspect';
+const DEFAULT_BRUSH_RADIUS: number = 0;
+const DEFAULT_POLICY: string = 'classic';
+const POLICIES: string[] = [
+  'classic',
+  'profit',
+  'fixed-quota',
+  'tree-density',
+  'sustainable-yield',
+  'none',
+];
+const DEFAULT_JACK_MOVEMENT: string = 'random';
+const JACK_MOVEMENTS: string[] = [
+  'random',
+  'greedy',
+  'avoid-bears',
+  'avoid-saplings',
+];
+const DEFAULT_BEAR_MOVEMENT: string = 'random';
+const BEAR_MOVEMENTS: string[] = [
+  'random',
+  'hunt',
+];
+const DEFAULT_RULES: IRules = {
+  bear_lifecycle: false,
+  economy: false,
+  policy: DEFAULT_POLICY,
+  jack_movement: DEFAULT_JACK_MOVEMENT,
+  bear_movement: DEFAULT_BEAR_MOVEMENT,
+  density: {
+    trees: 0.5,
+    jacks: 0.1,
+    bears: 0.02,
+  },
+  tree_threads: 0,
+};
+const CHART_YEARS: number = 100;
+
+// The name of a policy or movement strategy without its parameters, e.g. 'hunt' for 'hunt:4:5'.
+function get_rule_name(rule: string): string {
+  return rule.split(':')[0];
 }
 
-export function get_tree_amount(forestInfo: IForestInfo): number {
-  return forestInfo.map.reduce((acc, cell) => {
-    if ((cell & 0x00ff) > 0) {
-      return acc + 1;
-    }
+export function apply_frame(forestInfo: IForestInfo, frame: IForestFrame): IForestInfo {
+  const { changes, ...summary } = frame;
+  const map = forestInfo.map.slice();
+  for (let i = 0; i < changes.length; i += 2) {
+    map[changes[i]] = changes[i + 1];
+  }
 
-    return acc;
-  }, 0);
+  return { ...forestInfo, ...summary, map };
 }
 
 export function show_formatted_date(forestInfo: IForestInfo): string {
@@ -47,71 +110,258 @@ export function show_formatted_date(forestInfo: IForestInfo): string {
 }
 
 export default function App(props: {}): ReactElement<any, any> {
-  const [timer, setTimer] = useState<NodeJS.Timer | null>(null);
+  const [playing, setPlaying] = useState<boolean>(false);
 
   const [forestInfo, setForestInfo] = useState<IForestInfo>({
+    id: 0,
+    name: '',
+    sequence: 0,
     map: [],
     width: DEFAULT_WIDTH,
     height: DEFAULT_HEIGHT,
     months_elapsed: 0,
     yearly_lumber: 0,
     yearly_mauls: 0,
+    lumber_stockpile: 0,
+    funds: 0,
+    policy: DEFAULT_POLICY,
+    jack_movement: DEFAULT_JACK_MOVEMENT,
+    bear_movement: DEFAULT_BEAR_MOVEMENT,
+    trees: 0,
+    jacks: 0,
+    bears: 0,
+    can_undo: false,
+    can_redo: false,
   });
+  const [forests, setForests] = useState<IForestSummary[]>([]);
   const [seed, setSeed] = useState<number>(DEFAULT_SEED);
 
   const [seedInput, setSeedInput] = useState<string>(DEFAULT_SEED.toString());
   const [widthInput, setWidthInput] = useState<string>(DEFAULT_WIDTH.toString());
   const [heightInput, setHeightInput] = useState<string>(DEFAULT_HEIGHT.toString());
   const [cellInput, setCellInput] = useState<string>(DEFAULT_CELL_DRAW_SIZE.toString());
+  const [rulesInput, setRulesInput] = useState<IRules>(DEFAULT_RULES);
+  const [stats, setStats] = useState<IYearlyStats[]>([]);
+  const [settingsError, setSettingsError] = useState<string | null>(null);
+  const [stepInput, setStepInput] = useState<string>(DEFAULT_STEP_MONTHS.toString());
+  const [stopConditionInput, setStopConditionInput] = useState<string>(DEFAULT_STOP_CONDITION);
+  const [running, setRunning] = useState<boolean>(false);
+  const [speedInput, setSpeedInput] = useState<string>(DEFAULT_SPEED.toString());
+  const [paintToolInput, setPaintToolInput] = useState<string>(DEFAULT_PAINT_TOOL);
+  const [brushInput, setBrushInput] = useState<string>(DEFAULT_BRUSH_RADIUS.toString());
+  const [rewindInput, setRewindInput] = useState<string>('0');
+  const [historyError, setHistoryError] = useState<string | null>(null);
+
+  // The shown forest and the last frame applied to forestInfo, kept outside of React
+  // state so frame events arriving between renders see them immediately.
+  const forestId = useRef<number>(0);
+  const sequence = useRef<number>(-1);
+  const resyncing = useRef<boolean>(false);
+
+  const applyInfo = (info: IForestInfo): void => {
+    if (info.id !== forestId.current) return;
+    if (info.sequence < sequence.current) return;
+    sequence.current = info.sequence;
+    setForestInfo(info);
+  }
 
-  const getForest = () => {
-    get_forest().then(info => setForestInfo(info));
+  const resync = (): void => {
+    if (resyncing.current) return;
+    resyncing.current = true;
+    resync_forest(forestId.current)
+      .then(applyInfo)
+      .finally(() => resyncing.current = false);
   }
 
-  const handleUpdateSettings = (event: any): void => {
+  const applyFrame = (frame: IForestFrame): void => {
+    if (frame.id !== forestId.current) return;
+    if (frame.sequence <= sequence.current) return;
+    if (frame.sequence !== sequence.current + 1) {
+      resync();
+      return;
+    }
+
+    sequence.current = frame.sequence;
+    setForestInfo(info => apply_frame(info, frame));
+  }
+
+  const refreshForests = (): void => {
+    list_forests().then(setForests);
+  }
+
+  const selectForest = (id: number): void => {
+    forestId.current = id;
+    sequence.current = -1;
+    get_forest(id).then(applyInfo);
+    get_rules(id).then(setRulesInput);
+    is_simulation_running(id).then(running => setPlaying(running));
+  }
+
+  const createForest = (replace: boolean): void => {
     const seed = Number(seedInput);
     const width = Number(widthInput);
     const height = Number(heightInput);
 
     create_forest({
+      id: replace ? forestId.current : undefined,
       seed: seed,
       width: width,
       height: height,
+      rules: rulesInput,
     }).then(info => {
       if (!info) return;
-      setForestInfo(info);
+      if (!replace) {
+        forestId.current = info.id;
+        sequence.current = -1;
+        setPlaying(false);
+      }
+      applyInfo(info);
       setSeed(seed);
+      setSettingsError(null);
+      refreshForests();
+    }).catch(err => setSettingsError(String(err)));
+  }
+
+  const handleUpdateSettings = (event: any): void => {
+    createForest(true);
+  }
+
+  const handleNewForest = (event: any): void => {
+    createForest(false);
+  }
+
+  const handleApplyRules = (event: any): void => {
+    set_rules(forestId.current, rulesInput)
+      .then(frame => {
+        applyFrame(frame);
+        setSettingsError(null);
+      })
+      .catch(err => setSettingsError(String(err)));
+  }
+
+  const setRules = (rules: Partial<IRules>): void => {
+    setRulesInput({ ...rulesInput, ...rules });
+  }
+
+  const setDensity = (density: Partial<IRules['density']>): void => {
+    setRules({ density: { ...rulesInput.density, ...density } });
+  }
+
+  const handleCloseForest = (event: any): void => {
+    const others = forests.filter(forest => forest.id !== forestId.current);
+    if (others.length === 0) return;
+
+    remove_forest(forestId.current).then(_ => {
+      selectForest(others[0].id);
+      refreshForests();
     });
   }
 
+  const stepForest = (): void => {
+    step_forest(forestId.current, Math.max(1, Number(stepInput))).then(applyFrame);
+  }
+
   const handleRunSimulation = (event: any): void => {
-    if (timer === null) {
-      update_forest().then(_ => getForest());
-      setTimer(setInterval(() => update_forest().then(_ => getForest()), 1000));
+    if (!playing) {
+      start_simulation(forestId.current, Math.max(1, Number(speedInput))).then(_ => setPlaying(true));
     } else {
-      clearInterval(timer);
-      setTimer(null);
+      pause_simulation(forestId.current).then(_ => setPlaying(false));
+    }
+  }
+
+  const handleSpeedChange = (value: string): void => {
+    setSpeedInput(value);
+    if (playing && Number(value) >= 1) {
+      set_speed(forestId.current, Number(value));
     }
   }
 
   const handleUpdateMap = (event: any): void => {
-    update_forest().then(_ => getForest());
+    stepForest();
+  }
+
+  const handleRunUntil = (event: any): void => {
+    setRunning(true);
+    run_until(forestId.current, STOP_CONDITIONS[stopConditionInput])
+      .then(applyFrame)
+      .finally(() => setRunning(false));
+  }
+
+  const handleHistory = (request: Promise<IForestInfo>): void => {
+    request
+      .then(info => {
+        applyInfo(info);
+        setHistoryError(null);
+      })
+      .catch(err => setHistoryError(String(err)));
+  }
+
+  const handleUndo = (event: any): void => {
+    handleHistory(undo(forestId.current));
+  }
+
+  const handleRedo = (event: any): void => {
+    handleHistory(redo(forestId.current));
+  }
+
+  const handleRewind = (event: any): void => {
+    handleHistory(rewind_to(forestId.current, Math.max(0, Number(rewindInput))));
   }
 
   useEffect(() => {
-    getForest();
+    list_forests().then(list => {
+      setForests(list);
+      if (list.length > 0) {
+        selectForest(list[0].id);
+      }
+    });
   }, [props]);
 
+  const year = Math.floor(forestInfo.months_elapsed / 12);
+  useEffect(() => {
+    if (forestInfo.id === 0) return;
+    get_statistics(forestInfo.id, { start: Math.max(0, year - CHART_YEARS) }).then(setStats);
+  }, [forestInfo.id, year]);
+
+  useEffect(() => {
+    const timer = setInterval(refreshForests, 1000);
+    return () => clearInterval(timer);
+  }, []);
+
+  useEffect(() => {
+    const unlisten = on_forest_frame(applyFrame);
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, []);
+
   return (
     <div id="App_container">
       <div id="App_canvas">
-        <Map info={forestInfo} cellSize={Number(cellInput)} />
+        <Map
+          forestId={forestInfo.id}
+          info={forestInfo}
+          cellSize={Number(cellInput)}
+          paintEdit={PAINT_TOOLS[paintToolInput]}
+          brushRadius={Math.max(0, Number(brushInput))}
+          onPaint={applyFrame}
+        />
       </div>
 
       <div id="App_information">
         <div id="App_settings">
           <div id="settings-header">Simulation Settings</div>
 
+          <div className="settings-field">
+            <span>Forest:</span>
+            <select value={forestInfo.id}
+              onChange={(event) => {
+                selectForest(Number(event.target.value));
+              }}>
+              {forests.map(forest => <option key={forest.id} value={forest.id}>{forest.name}</option>)}
+            </select>
+          </div>
+
           <div className="settings-field">
             <span>Seed:</span>
             <input type="number" value={seedInput}
@@ -137,6 +387,84 @@ export default function App(props: {}): ReactElement<any, any> {
               }} />
           </div>
 
+          <div className="settings-field">
+            <span>Policy:</span>
+            <select value={get_rule_name(rulesInput.policy)}
+              onChange={(event) => {
+                setRules({ policy: event.target.value });
+              }}>
+              {POLICIES.map(policy => <option key={policy} value={policy}>{policy}</option>)}
+            </select>
+          </div>
+
+          <div className="settings-field">
+            <span>Jack Movement:</span>
+            <select value={get_rule_name(rulesInput.jack_movement)}
+              onChange={(event) => {
+                setRules({ jack_movement: event.target.value });
+              }}>
+              {JACK_MOVEMENTS.map(movement => <option key={movement} value={movement}>{movement}</option>)}
+            </select>
+          </div>
+
+          <div className="settings-field">
+            <span>Bear Movement:</span>
+            <select value={get_rule_name(rulesInput.bear_movement)}
+              onChange={(event) => {
+                setRules({ bear_movement: event.target.value });
+              }}>
+              {BEAR_MOVEMENTS.map(movement => <option key={movement} value={movement}>{movement}</option>)}
+            </select>
+          </div>
+
+          <div className="settings-field">
+            <span>Bear Lifecycle:</span>
+            <input type="checkbox" checked={rulesInput.bear_lifecycle}
+              onChange={(event) => {
+                setRules({ bear_lifecycle: event.target.checked });
+              }} />
+          </div>
+
+          <div className="settings-field">
+            <span>Economy:</span>
+            <input type="checkbox" checked={rulesInput.economy}
+              onChange={(event) => {
+                setRules({ economy: event.target.checked });
+              }} />
+          </div>
+
+          <div className="settings-field">
+            <span>Tree Density:</span>
+            <input type="number" min="0" max="1" step="0.01" value={rulesInput.density.trees}
+              onChange={(event) => {
+                setDensity({ trees: Number(event.target.value) });
+              }} />
+          </div>
+
+          <div className="settings-field">
+            <span>Jack Density:</span>
+            <input type="number" min="0" max="1" step="0.01" value={rulesInput.density.jacks}
+              onChange={(event) => {
+                setDensity({ jacks: Number(event.target.value) });
+              }} />
+          </div>
+
+          <div className="settings-field">
+            <span>Bear Density:</span>
+            <input type="number" min="0" max="1" step="0.01" value={rulesInput.density.bears}
+              onChange={(event) => {
+                setDensity({ bears: Number(event.target.value) });
+              }} />
+          </div>
+
+          <div className="settings-field">
+            <span>Tree Threads:</span>
+            <input type="number" min="0" value={rulesInput.tree_threads}
+              onChange={(event) => {
+                setRules({ tree_threads: Math.max(0, Number(event.target.value)) });
+              }} />
+          </div>
+
           <div className="settings-field">
             <span>Cell Size:</span>
             <input type="range" min="1" max="64" value={cellInput}
@@ -146,7 +474,30 @@ export default function App(props: {}): ReactElement<any, any> {
             />
           </div>
 
+          <div className="settings-field">
+            <span>Paint Tool:</span>
+            <select value={paintToolInput}
+              onChange={(event) => {
+                setPaintToolInput(event.target.value);
+              }}>
+              {Object.keys(PAINT_TOOLS).map(tool => <option key={tool} value={tool}>{tool}</option>)}
+            </select>
+          </div>
+
+          <div className="settings-field">
+            <span>Brush Radius:</span>
+            <input type="number" min="0" value={brushInput}
+              onChange={(event) => {
+                setBrushInput(event.target.value);
+              }} />
+          </div>
+
+          {settingsError !== null && <div id="settings-error">{settingsError}</div>}
+
           <button onClick={handleUpdateSettings}>Update Settings</button>
+          <button onClick={handleApplyRules}>Apply Rules</button>
+          <button onClick={handleNewForest}>New Forest</button>
+          <button onClick={handleCloseForest} disabled={forests.length < 2}>Close Forest</button>
         </div>
 
         <hr />
@@ -156,19 +507,24 @@ export default function App(props: {}): ReactElement<any, any> {
 
           <div>{show_formatted_date(forestInfo)}</div>
 
+          <div className="stats-field">
+            <div>Policy:</div>
+            <div>{forestInfo.policy}</div>
+          </div>
+
           <div className="stats-field">
             <div>Bears:</div>
-            <div>{get_bear_amount(forestInfo)}</div>
+            <div>{forestInfo.bears}</div>
           </div>
 
           <div className="stats-field">
             <div>Jacks:</div>
-            <div>{get_jack_amount(forestInfo)}</div>
+            <div>{forestInfo.jacks}</div>
           </div>
 
           <div className="stats-field">
             <div>Trees:</div>
-            <div>{get_tree_amount(forestInfo)}</div>
+            <div>{forestInfo.trees}</div>
           </div>
 
           <div className="stats-field">
@@ -180,18 +536,120 @@ export default function App(props: {}): ReactElement<any, any> {
             <div>Yearly Mauls:</div>
             <div>{forestInfo.yearly_mauls}</div>
           </div>
+
+          <div className="stats-field">
+            <div>Lumber Stockpile:</div>
+            <div>{forestInfo.lumber_stockpile}</div>
+          </div>
+
+          <div className="stats-field">
+            <div>Funds:</div>
+            <div>{forestInfo.funds}</div>
+          </div>
+
+          <Chart stats={stats} />
         </div>
 
         <hr />
 
         <div id="App_buttons">
-          <button onClick={handleRunSimulation}>
-            {timer === null ? 'Start Simulation' : 'Stop Simulation'}
+          <div className="buttons-field">
+            <span>Months per Second:</span>
+            <input type="number" min="1" max="1200" value={speedInput}
+              onChange={(event) => {
+                handleSpeedChange(event.target.value);
+              }} />
+          </div>
+
+          <button onClick={handleRunSimulation} disabled={running}>
+            {playing ? 'Stop Simulation' : 'Start Simulation'}
           </button>
 
-          <button onClick={handleUpdateMap} disabled={timer !== null}>
+          <div className="buttons-field">
+            <span>Months per Step:</span>
+            <input type="number" min="1" value={stepInput}
+              onChange={(event) => {
+                setStepInput(event.target.value);
+              }} />
+          </div>
+
+          <button onClick={handleUpdateMap} disabled={playing || running}>
             Update
           </button>
+
+          <div className="buttons-field">
+            <span>Run Until:</span>
+            <select value={stopConditionInput}
+              onChange={(event) => {
+                setStopConditionInput(event.target.value);
+              }}>
+              {Object.keys(STOP_CONDITIONS).map(name => <option key={name} value={name}>{name}</option>)}
+            </select>
+          </div>
+
+          <button onClick={handleRunUntil} disabled={playing || running}>
+            {running ? 'Running...' : 'Run'}
+          </button>
+        </div>
+
+        <hr />
+
+        <div id="App_history">
+          <div className="history-buttons">
+            <button onClick={handleUndo} disabled={running || !forestInfo.can_undo}>Undo</button>
+            <button onClick={handleRedo} disabled={running || !forestInfo.can_redo}>Redo</button>
+          </div>
+
+          <div className="history-field">
+            <span>Rewind to Month:</span>
+            <input type="number" min="0" value={rewindInput}
+              onChange={(event) => {
+                setRewindInput(event.target.value);
+              }} />
+          </div>
+
+          <button onClick={handleRewind} disabled={running}>Rewind</button>
+
+          {historyError !== null && <div id="history-error">{historyError}</div>}
+        </div>
+
+        <hr />
+
+        <div id="App_forests">
+          <div id="forests-header">Open Forests</div>
+
+          <table>
+            <thead>
+              <tr>
+                <th>Name</th>
+                <th>Month</th>
+                <th>Trees</th>
+                <th>Jacks</th>
+                <th>Bears</th>
+                <th>Lumber</th>
+                <th>Mauls</th>
+                <th>Funds</th>
+                <th>Policy</th>
+              </tr>
+            </thead>
+            <tbody>
+              {forests.map(forest =>
+                <tr key={forest.id}
+                  className={forest.id === forestInfo.id ? 'selected' : undefined}
+                  onClick={() => selectForest(forest.id)}>
+                  <td>{forest.name}</td>
+                  <td>{forest.months_elapsed}</td>
+                  <td>{forest.trees}</td>
+                  <td>{forest.jacks}</td>
+                  <td>{forest.bears}</td>
+                  <td>{forest.yearly_lumber}</td>
+                  <td>{forest.yearly_mauls}</td>
+                  <td>{forest.funds}</td>
+                  <td>{forest.policy}</td>
+                </tr>
+              )}
+            </tbody>
+          </table>
         </div>
       </div>
     </div>
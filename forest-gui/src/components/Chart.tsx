@@ -0,0 +1,48 @@
+import { ReactElement } from "react";
+import { IYearlyStats } from "../types/response";
+import "./Chart.css";
+
+const CHART_WIDTH: number = 230;
+const CHART_HEIGHT: number = 100;
+const SERIES: { key: 'trees' | 'jacks' | 'bears', color: string }[] = [
+  { key: 'trees', color: '#65c399' },
+  { key: 'jacks', color: '#ffd54f' },
+  { key: 'bears', color: '#ff8a80' },
+];
+
+// Each series is scaled to its own peak so bears stay visible next to thousands of trees.
+function get_points(stats: IYearlyStats[], key: 'trees' | 'jacks' | 'bears'): string {
+  const peak = Math.max(1, ...stats.map(year => year[key]));
+  const step = CHART_WIDTH / Math.max(1, stats.length - 1);
+
+  return stats
+    .map((year, i) => `${i * step},${CHART_HEIGHT - (year[key] / peak) * CHART_HEIGHT}`)
+    .join(' ');
+}
+
+export default function Chart(props: { stats: IYearlyStats[] }): ReactElement<any, any> {
+  const first = props.stats[0];
+  const last = props.stats[props.stats.length - 1];
+
+  return (
+    <div id="Chart_container">
+      <svg viewBox={`0 0 ${CHART_WIDTH} ${CHART_HEIGHT}`} preserveAspectRatio="none">
+        {SERIES.map(series =>
+          <polyline key={series.key} points={get_points(props.stats, series.key)} stroke={series.color} />
+        )}
+      </svg>
+
+      <div id="Chart_legend">
+        {SERIES.map(series =>
+          <span key={series.key} style={{ color: series.color }}>
+            {series.key} {Math.max(0, ...props.stats.map(year => year[series.key]))}
+          </span>
+        )}
+      </div>
+
+      <div id="Chart_years">
+        {first === undefined ? 'No years recorded' : `Years ${first.year} to ${last.year}`}
+      </div>
+    </div>
+  );
+}
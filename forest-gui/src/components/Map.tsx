@@ -1,22 +1,26 @@
 import { ReactElement, useEffect, useRef, useState } from "react";
-import { IForestInfo } from "../types/response";
+import { inspect_cell, paint_cells, PaintEdit } from "../commands/forest_commands";
+import { ICellDetails, IForestFrame, IForestInfo } from "../types/response";
 import './Map.css';
 
 export interface ISelectedCell {
   cell: number;
   x: number;
   y: number;
+  details: ICellDetails | null;
 }
 
 export class SelectedCell implements ISelectedCell {
   cell: number;
   x: number;
   y: number;
+  details: ICellDetails | null;
 
-  constructor(cell: number, x: number, y: number) {
+  constructor(cell: number, x: number, y: number, details: ICellDetails | null = null) {
     this.cell = cell;
     this.x = x;
     this.y = y;
+    this.details = details;
   }
 }
 
@@ -90,7 +94,16 @@ export function draw_pixel(
   }
 }
 
-export function show_tree_info(cell: number): string {
+export function show_tree_info(cell: number, details: ICellDetails | null = null): string {
+  if (details) {
+    const tree = details.tree;
+    if (!tree) {
+      return 'None';
+    }
+
+    return `${tree.kind}, age ${tree.age}, harvest ${tree.harvest_chance}%`;
+  }
+
   let tree_age = (cell & 0x00ff) >> 4 * 0;
   if (tree_age === 0) {
     return 'None'
@@ -99,7 +112,16 @@ export function show_tree_info(cell: number): string {
   return `age ${tree_age}`;
 }
 
-export function show_jack_info(cell: number): string {
+export function show_jack_info(cell: number, details: ICellDetails | null = null): string {
+  if (details) {
+    const jack = details.jack;
+    if (!jack) {
+      return 'None';
+    }
+
+    return `level ${jack.level}, maul ${jack.maul_chance}%`;
+  }
+
   let jack_level = (cell & 0x0f00) >> 4 * 2;
   if (jack_level === 0) {
     return 'None'
@@ -108,7 +130,20 @@ export function show_jack_info(cell: number): string {
   return `level ${jack_level}`;
 }
 
-export function show_bear_info(cell: number): string {
+export function show_bear_info(cell: number, details: ICellDetails | null = null): string {
+  if (details) {
+    const bear = details.bear;
+    if (!bear) {
+      return 'None';
+    }
+
+    if (bear.age === null || bear.food === null) {
+      return 'True';
+    }
+
+    return `age ${bear.age}, food ${bear.food}`;
+  }
+
   let bear = (cell & 0xf000) >> 4 * 3;
   if (bear === 0) {
     return 'None'
@@ -117,6 +152,15 @@ export function show_bear_info(cell: number): string {
   return 'True';
 }
 
+export function show_neighbour_info(details: ICellDetails | null): string {
+  if (!details) {
+    return '';
+  }
+
+  const neighbours = details.neighbours;
+  return `${neighbours.trees} trees, ${neighbours.jacks} jacks, ${neighbours.bears} bears`;
+}
+
 export interface IPosition {
   top?: number;
   left?: number;
@@ -125,8 +169,12 @@ export interface IPosition {
 }
 
 export default function Map(props: {
+  forestId: number,
   info: IForestInfo,
   cellSize: number,
+  paintEdit: PaintEdit | null,
+  brushRadius: number,
+  onPaint: (frame: IForestFrame) => void,
 }): ReactElement<any, any> {
   const canvasRef = useRef<HTMLCanvasElement>(null);
 
@@ -164,7 +212,27 @@ export default function Map(props: {
       props.info.width,
     );
 
-    setSelected(new SelectedCell(props.info.map[index], offsetX, offsetY));
+    if (props.paintEdit !== null) {
+      paint_cells(
+        props.forestId,
+        { kind: 'circle', x: canvasX, y: canvasY, radius: props.brushRadius },
+        props.paintEdit,
+      ).then(props.onPaint);
+      return;
+    }
+
+    const cell = props.info.map[index];
+    setSelected(new SelectedCell(cell, offsetX, offsetY));
+
+    inspect_cell(props.forestId, canvasX, canvasY).then(details => {
+      setSelected(selected => {
+        if (selected === null || selected.x !== offsetX || selected.y !== offsetY) {
+          return selected;
+        }
+
+        return new SelectedCell(cell, offsetX, offsetY, details);
+      });
+    });
   }
 
   useEffect(() => {
@@ -208,17 +276,21 @@ export default function Map(props: {
           }}>×</div>
 
           <div className="info-row">
-            <span>Tree: {show_tree_info(selected?.cell ?? 0)}</span>
+            <span>Tree: {show_tree_info(selected?.cell ?? 0, selected?.details)}</span>
           </div>
 
           <div className="info-row">
-            <span>Jack: {show_jack_info(selected?.cell ?? 0)}</span>
+            <span>Jack: {show_jack_info(selected?.cell ?? 0, selected?.details)}</span>
           </div>
 
           <div className="info-row">
-            <span>Bear: {show_bear_info(selected?.cell ?? 0)}</span>
+            <span>Bear: {show_bear_info(selected?.cell ?? 0, selected?.details)}</span>
             <span></span>
           </div>
+
+          <div className="info-row" hidden={!selected?.details}>
+            <span>Neighbours: {show_neighbour_info(selected?.details ?? null)}</span>
+          </div>
       </div>
     </div>
   );
@@ -1,8 +1,96 @@
-export interface IForestInfo {
-  map: number[];
+export interface IForestSummary {
+  id: number;
+  name: string;
   width: number;
   height: number;
   months_elapsed: number;
   yearly_lumber: number;
   yearly_mauls: number;
+  lumber_stockpile: number;
+  funds: number;
+  policy: string;
+  jack_movement: string;
+  bear_movement: string;
+  trees: number;
+  jacks: number;
+  bears: number;
+  can_undo: boolean;
+  can_redo: boolean;
+}
+
+export interface IForestInfo extends IForestSummary {
+  sequence: number;
+  map: number[];
+}
+
+export interface IForestFrame extends IForestSummary {
+  sequence: number;
+  // Flat [index, cell, index, cell, ...] pairs of the cells changed since the previous frame.
+  changes: number[];
+}
+
+export interface IRules {
+  bear_lifecycle: boolean;
+  economy: boolean;
+  // The same text forms the CLI accepts, e.g. 'fixed-quota:50:10' or 'hunt:4:5'.
+  policy: string;
+  jack_movement: string;
+  bear_movement: string;
+  // Starting shares of cells, only used when a forest is created.
+  density: {
+    trees: number;
+    jacks: number;
+    bears: number;
+  };
+  // Threads for the tree phase; only used by backends built with the parallel feature.
+  tree_threads: number;
+}
+
+export interface IYearlyStats {
+  year: number;
+  trees: number;
+  jacks: number;
+  bears: number;
+  lumber: number;
+  mauls: number;
+  bear_births: number;
+  bear_deaths: number;
+  jacks_hired: number;
+  jacks_fired: number;
+  stockpile: number;
+  lumber_sold: number;
+  revenue: number;
+  wages: number;
+  profit: number;
+  funds: number;
+}
+
+export interface ICellDetails {
+  x: number;
+  y: number;
+  index: number;
+  tree: {
+    kind: string;
+    age: number;
+    species: number;
+    spawn_chance: number;
+    harvest_chance: number;
+    harvest_amount: number;
+  } | null;
+  jack: {
+    level: number;
+    maul_chance: number;
+  } | null;
+  bear: {
+    count: number;
+    age: number | null;
+    food: number | null;
+  } | null;
+  flags: number;
+  neighbours: {
+    cells: number;
+    trees: number;
+    jacks: number;
+    bears: number;
+  };
 }
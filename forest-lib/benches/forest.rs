@@ -0,0 +1,138 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use forest_lib::{
+    forest::{Forest, Phase},
+    rules::Rules,
+};
+
+const SEED: u64 = 123123;
+
+const SIZES: [(&str, usize, usize); 3] =
+    [("small", 12, 8), ("medium", 120, 80), ("large", 500, 500)];
+
+// A forest that has run for a few decades, so every phase has real work to do.
+fn settled_forest(width: usize, height: usize, rules: Rules) -> Forest {
+    let mut forest = Forest::with_rules(SEED, width, height, rules);
+    while forest.months_elapsed < 240 {
+        forest.update();
+    }
+    forest
+}
+
+fn bench_new(c: &mut Criterion) {
+    let mut group = c.benchmark_group("new");
+
+    for (name, width, height) in SIZES {
+        group.bench_function(name, |b| b.iter(|| Forest::new(SEED, width, height)));
+    }
+
+    group.finish();
+}
+
+fn bench_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update");
+
+    for (name, width, height) in SIZES {
+        let forest = settled_forest(width, height, Rules::default());
+        group.bench_function(name, |b| {
+            b.iter_batched_ref(
+                || forest.clone(),
+                |forest| forest.update(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_thousand_months(c: &mut Criterion) {
+    let mut group = c.benchmark_group("1000_months");
+    group.sample_size(10);
+
+    for (name, width, height) in SIZES {
+        group.bench_function(name, |b| {
+            b.iter_batched_ref(
+                || Forest::new(SEED, width, height),
+                |forest| {
+                    for _ in 0..1000 {
+                        forest.update();
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_phases(c: &mut Criterion) {
+    let mut group = c.benchmark_group("phase");
+
+    for (name, width, height) in SIZES.into_iter().skip(1) {
+        let forest = settled_forest(width, height, Rules::default());
+        // The bear lifecycle phase does nothing unless the rule is on.
+        let lifecycle = settled_forest(
+            width,
+            height,
+            Rules {
+                bear_lifecycle: true,
+                ..Rules::default()
+            },
+        );
+
+        for phase in Phase::ALL {
+            let forest = match phase {
+                Phase::BearLifecycle => &lifecycle,
+                _ => &forest,
+            };
+
+            group.bench_with_input(BenchmarkId::new(phase.name(), name), &phase, |b, &phase| {
+                b.iter_batched_ref(
+                    || forest.clone(),
+                    |forest| forest.run_phase(phase),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+
+    group.finish();
+}
+
+#[cfg(feature = "parallel")]
+fn bench_parallel_trees(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel_trees");
+
+    for (name, width, height) in SIZES.into_iter().skip(1) {
+        let forest = settled_forest(width, height, Rules::default());
+
+        for threads in [1, 2, 4, 8] {
+            let mut forest = forest.clone();
+            forest.rules.tree_threads = threads;
+
+            group.bench_with_input(BenchmarkId::new(name, threads), &forest, |b, forest| {
+                b.iter_batched_ref(
+                    || forest.clone(),
+                    |forest| forest.run_phase(Phase::Trees),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+
+    group.finish();
+}
+
+#[cfg(not(feature = "parallel"))]
+fn bench_parallel_trees(_c: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    bench_new,
+    bench_update,
+    bench_thousand_months,
+    bench_phases,
+    bench_parallel_trees
+);
+criterion_main!(benches);
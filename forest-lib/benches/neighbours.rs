@@ -0,0 +1,73 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use forest_lib::forest::Forest;
+
+// The neighbour lookup as it was before `Neighbours`, kept here as the baseline to beat.
+fn allocating_adjacent_positions(width: usize, height: usize, index: usize) -> Vec<usize> {
+    let mut positions: Vec<usize> = vec![];
+
+    let adjacent_movements: Vec<(isize, isize)> = vec![
+        (-1, -1),
+        (0, -1),
+        (1, -1),
+        (-1, 0),
+        (1, 0),
+        (-1, 1),
+        (0, 1),
+        (1, 1),
+    ];
+
+    let x = index % width;
+    let y = index / width;
+
+    for movement in adjacent_movements {
+        let x = x as isize + movement.0;
+        let y = y as isize + movement.1;
+        if x < 0 || y < 0 {
+            continue;
+        }
+
+        let x = x as usize;
+        let y = y as usize;
+        if x >= width || y >= height {
+            continue;
+        }
+
+        positions.push(y * width + x);
+    }
+
+    positions
+}
+
+fn bench_neighbours(c: &mut Criterion) {
+    let mut group = c.benchmark_group("neighbours");
+
+    for size in [250, 1000] {
+        let forest = Forest::new(123123, size, size);
+        let cells = size * size;
+
+        group.bench_with_input(BenchmarkId::new("allocating", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut total = 0;
+                for i in 0..cells {
+                    total += allocating_adjacent_positions(size, size, black_box(i)).len();
+                }
+                total
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("inline", size), &size, |b, _| {
+            b.iter(|| {
+                let mut total = 0;
+                for i in 0..cells {
+                    total += forest.get_adjacent_positions(black_box(i)).len();
+                }
+                total
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_neighbours);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+use crate::consts::{BEAR_MASK, BEAR_SHIFT, JACK_MASK, JACK_SHIFT, TREE_MASK, TREE_SHIFT};
+
+/// A single map cell. From the low bits up: 12 bits of tree age, a 4 bit tree species,
+/// an 8 bit jack level, a 4 bit bear field and 4 bits of free per-cell flags.
+pub type Cell = u32;
+
+const LEGACY_TREE_SHIFT: u32 = 0;
+const LEGACY_JACK_SHIFT: u32 = 8;
+const LEGACY_BEAR_SHIFT: u32 = 12;
+const LEGACY_FIELD_MAX: [Cell; 3] = [0xFF, 0xF, 0xF];
+
+/// Packs a cell into the original `u16` layout used by the GUI: 8 bits of tree age, then
+/// 4 bits each of jack level and bear. Values too large for a field are saturated, and
+/// species and flags are dropped.
+pub fn to_legacy(cell: Cell) -> u16 {
+    let tree = Cell::min((cell & TREE_MASK) >> TREE_SHIFT, LEGACY_FIELD_MAX[0]);
+    let jack = Cell::min((cell & JACK_MASK) >> JACK_SHIFT, LEGACY_FIELD_MAX[1]);
+    let bear = Cell::min((cell & BEAR_MASK) >> BEAR_SHIFT, LEGACY_FIELD_MAX[2]);
+
+    ((tree << LEGACY_TREE_SHIFT) | (jack << LEGACY_JACK_SHIFT) | (bear << LEGACY_BEAR_SHIFT)) as u16
+}
+
+pub fn from_legacy(cell: u16) -> Cell {
+    let cell = cell as Cell;
+    let tree = (cell >> LEGACY_TREE_SHIFT) & LEGACY_FIELD_MAX[0];
+    let jack = (cell >> LEGACY_JACK_SHIFT) & LEGACY_FIELD_MAX[1];
+    let bear = (cell >> LEGACY_BEAR_SHIFT) & LEGACY_FIELD_MAX[2];
+
+    (tree << TREE_SHIFT) | (jack << JACK_SHIFT) | (bear << BEAR_SHIFT)
+}
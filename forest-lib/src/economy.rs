@@ -0,0 +1,63 @@
+use crate::cell::Cell;
+use crate::consts::{
+    JACK_BASE_WAGE, JACK_HIRING_COST, JACK_LEVEL_WAGE, LUMBER_PRICE, LUMBER_SALE_PERCENT,
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Economy {
+    /// Wide enough that a large regional map can't fill it.
+    pub stockpile: u64,
+    pub funds: i64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Accounts {
+    pub lumber_sold: u64,
+    pub revenue: i64,
+    pub wages: i64,
+    pub profit: i64,
+}
+
+impl Economy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn store(&mut self, lumber: u32) {
+        self.stockpile += lumber as u64;
+    }
+
+    pub fn close_year(&mut self, wages: u64) -> Accounts {
+        let lumber_sold = (self.stockpile * LUMBER_SALE_PERCENT as u64).div_ceil(100);
+        let revenue = lumber_sold as i64 * LUMBER_PRICE as i64;
+        let wages = wages as i64;
+        let profit = revenue - wages;
+
+        self.stockpile -= lumber_sold;
+        self.funds += profit;
+
+        Accounts {
+            lumber_sold,
+            revenue,
+            wages,
+            profit,
+        }
+    }
+
+    pub fn jack_wage(level: Cell) -> u64 {
+        (JACK_BASE_WAGE + level * JACK_LEVEL_WAGE) as u64
+    }
+
+    pub fn affordable_hires(&self, profit: i64) -> usize {
+        let budget = i64::min(profit, self.funds);
+        if budget <= 0 {
+            return 0;
+        }
+
+        (budget / JACK_HIRING_COST as i64) as usize
+    }
+
+    pub fn pay_hiring_cost(&mut self) {
+        self.funds -= JACK_HIRING_COST as i64;
+    }
+}
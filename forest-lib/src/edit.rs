@@ -0,0 +1,132 @@
+use std::{error::Error, fmt};
+
+use crate::cell::Cell;
+use crate::consts::{JACK_MAX_LEVEL, SPECIES_MASK, SPECIES_SHIFT, TREE_MAX_AGE};
+
+/// A change to make to every cell under a brush.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edit {
+    /// Plants a tree, replacing any tree already there.
+    SetTree { age: Cell, species: Cell },
+    ClearTree,
+    /// Places a jack, replacing any jack already there.
+    PlaceJack { level: Cell },
+    RemoveJack,
+    /// Places a bear unless the cell already has one.
+    PlaceBear,
+    RemoveBear,
+}
+
+impl Edit {
+    pub fn validate(&self) -> Result<(), EditError> {
+        match *self {
+            Edit::SetTree { age, .. } if age == 0 || age > TREE_MAX_AGE => {
+                Err(EditError::TreeAge { age })
+            }
+            Edit::SetTree { species, .. } if species > SPECIES_MASK >> SPECIES_SHIFT => {
+                Err(EditError::Species { species })
+            }
+            Edit::PlaceJack { level } if level == 0 || level > JACK_MAX_LEVEL => {
+                Err(EditError::JackLevel { level })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The cells an edit applies to. Rectangles and circles are clipped to the map, but
+/// their anchor cell must be on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Brush {
+    Cell { x: usize, y: usize },
+    /// `width` by `height` cells with `(x, y)` as the top left corner.
+    Rect {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Every cell within `radius` of `(x, y)`.
+    Circle { x: usize, y: usize, radius: usize },
+}
+
+impl Brush {
+    /// The indices under the brush on a `width` by `height` map, in ascending order.
+    pub fn indices(&self, width: usize, height: usize) -> Result<Vec<usize>, EditError> {
+        let (x, y) = match *self {
+            Brush::Cell { x, y } | Brush::Rect { x, y, .. } | Brush::Circle { x, y, .. } => (x, y),
+        };
+
+        if x >= width || y >= height {
+            return Err(EditError::OutOfBounds { x, y });
+        }
+
+        // Every cell is within `width + height` of the anchor, so a larger radius covers the
+        // same cells and clamping keeps the arithmetic below in range.
+        let radius = match *self {
+            Brush::Circle { radius, .. } => radius.min(width.saturating_add(height)),
+            _ => 0,
+        };
+
+        let (left, top, right, bottom) = match *self {
+            Brush::Cell { .. } => (x, y, x + 1, y + 1),
+            Brush::Rect {
+                width: w,
+                height: h,
+                ..
+            } => (x, y, x.saturating_add(w), y.saturating_add(h)),
+            Brush::Circle { .. } => (
+                x.saturating_sub(radius),
+                y.saturating_sub(radius),
+                x.saturating_add(radius).saturating_add(1),
+                y.saturating_add(radius).saturating_add(1),
+            ),
+        };
+
+        let mut indices = vec![];
+        for cy in top..usize::min(bottom, height) {
+            for cx in left..usize::min(right, width) {
+                if let Brush::Circle { .. } = *self {
+                    let (dx, dy) = (cx.abs_diff(x) as u128, cy.abs_diff(y) as u128);
+                    if (dx * dx).saturating_add(dy * dy) > radius as u128 * radius as u128 {
+                        continue;
+                    }
+                }
+
+                indices.push(cy * width + cx);
+            }
+        }
+
+        Ok(indices)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditError {
+    OutOfBounds { x: usize, y: usize },
+    TreeAge { age: Cell },
+    Species { species: Cell },
+    JackLevel { level: Cell },
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::OutOfBounds { x, y } => write!(f, "({}, {}) is outside the map", x, y),
+            EditError::TreeAge { age } => {
+                write!(f, "tree age {} is not between 1 and {}", age, TREE_MAX_AGE)
+            }
+            EditError::Species { species } => write!(
+                f,
+                "species {} is not between 0 and {}",
+                species,
+                SPECIES_MASK >> SPECIES_SHIFT
+            ),
+            EditError::JackLevel { level } => {
+                write!(f, "jack level {} is not between 1 and {}", level, JACK_MAX_LEVEL)
+            }
+        }
+    }
+}
+
+impl Error for EditError {}
@@ -0,0 +1,37 @@
+use std::{error::Error, fmt};
+
+use crate::consts::MAX_CELLS;
+use crate::rules::Density;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ForestError {
+    /// The map would have no cells.
+    ZeroDimensions { width: usize, height: usize },
+    /// `width * height` is above `MAX_CELLS`.
+    TooLarge { width: usize, height: usize },
+    /// A starting density is negative, above one or not a number.
+    InvalidDensity { density: Density },
+    /// Zero is a fixed point of the random generator, so every roll would come up zero.
+    DegenerateSeed,
+}
+
+impl fmt::Display for ForestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForestError::ZeroDimensions { width, height } => {
+                write!(f, "a {}x{} forest has no cells", width, height)
+            }
+            ForestError::TooLarge { width, height } => {
+                write!(f, "a {}x{} forest has more than {} cells", width, height, MAX_CELLS)
+            }
+            ForestError::InvalidDensity { density } => write!(
+                f,
+                "starting densities {} must each be between 0 and 1",
+                density
+            ),
+            ForestError::DegenerateSeed => write!(f, "the seed must not be 0"),
+        }
+    }
+}
+
+impl Error for ForestError {}
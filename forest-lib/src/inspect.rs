@@ -0,0 +1,69 @@
+use std::fmt;
+
+use crate::cell::Cell;
+use crate::forest::{Bear, TreeKind};
+
+/// Everything known about one cell, as returned by `Forest::inspect`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellInfo {
+    pub index: usize,
+    pub position: (usize, usize),
+    pub cell: Cell,
+    pub tree: Option<TreeInfo>,
+    pub jack: Option<JackInfo>,
+    pub bear: Option<BearInfo>,
+    pub flags: Cell,
+    pub neighbours: NeighbourCounts,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TreeInfo {
+    pub kind: TreeKind,
+    pub age: Cell,
+    pub species: Cell,
+    /// Percent chance each month of seeding a sapling into a free neighbour.
+    pub spawn_chance: u32,
+    /// Percent chance a jack arriving on the tree cuts it down.
+    pub harvest_chance: u32,
+    /// Lumber the tree yields when cut down.
+    pub harvest_amount: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct JackInfo {
+    pub level: Cell,
+    /// Percent chance the jack is mauled when a bear reaches it.
+    pub maul_chance: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BearInfo {
+    /// How many bears the cell's bear field counts; more than one is a broken invariant.
+    pub count: Cell,
+    /// The bear's lifecycle record, if bear lifecycle is enabled.
+    pub lifecycle: Option<Bear>,
+}
+
+/// What the in-bounds neighbours of a cell hold.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NeighbourCounts {
+    pub cells: usize,
+    pub trees: usize,
+    pub jacks: usize,
+    pub bears: usize,
+}
+
+impl fmt::Display for TreeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TreeKind::None => "none",
+            TreeKind::Sapling => "sapling",
+            TreeKind::Mature => "mature",
+            TreeKind::Elder => "elder",
+        };
+        write!(f, "{}", name)
+    }
+}
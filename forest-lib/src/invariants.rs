@@ -0,0 +1,71 @@
+use std::{error::Error, fmt};
+
+use crate::cell::Cell;
+use crate::forest::Phase;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// A cell's bear field counts more than one bear.
+    StackedBears { bears: Cell },
+    /// A jack's level is above `JACK_MAX_LEVEL`, usually from a jack placed onto another.
+    JackLevel { level: Cell },
+    /// A cell has a tree species but no tree.
+    SpeciesWithoutTree { species: Cell },
+    /// The position index disagrees with the map about what a cell holds.
+    IndexMismatch { set: &'static str },
+    /// A bear on the map has no lifecycle record, or a record has no bear on the map.
+    UntrackedBear,
+    /// A bear's food or age is outside what the lifecycle allows.
+    BearOutOfRange { age: u16, food: u16 },
+    /// The yearly history is not in order.
+    HistoryOutOfOrder { year: u32 },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::StackedBears { bears } => write!(f, "{} bears share the cell", bears),
+            Violation::JackLevel { level } => write!(f, "jack level {} is above the maximum", level),
+            Violation::SpeciesWithoutTree { species } => {
+                write!(f, "species {} is set without a tree", species)
+            }
+            Violation::IndexMismatch { set } => write!(f, "{} index disagrees with the map", set),
+            Violation::UntrackedBear => write!(f, "bear and lifecycle records disagree"),
+            Violation::BearOutOfRange { age, food } => {
+                write!(f, "bear has age {} and food {}", age, food)
+            }
+            Violation::HistoryOutOfOrder { year } => write!(f, "history year {} is out of order", year),
+        }
+    }
+}
+
+/// A broken invariant, with where and when it was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvariantError {
+    pub month: u32,
+    /// The phase that had just run, if the check happened during an update.
+    pub phase: Option<Phase>,
+    pub cell: Option<usize>,
+    pub position: Option<(usize, usize)>,
+    pub violation: Violation,
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "month {}", self.month)?;
+
+        if let Some(phase) = self.phase {
+            write!(f, ", after {} phase", phase.name())?;
+        }
+
+        match (self.cell, self.position) {
+            (Some(cell), Some((x, y))) => write!(f, ", cell {} ({}, {})", cell, x, y)?,
+            (Some(cell), None) => write!(f, ", cell {}", cell)?,
+            _ => {}
+        }
+
+        write!(f, ": {}", self.violation)
+    }
+}
+
+impl Error for InvariantError {}
@@ -1,22 +1,44 @@
 mod random;
+pub mod cell;
+pub mod digest;
+pub mod economy;
+pub mod edit;
+pub mod error;
+pub mod inspect;
+pub mod invariants;
+pub mod movement;
+pub mod policy;
+pub mod positions;
+pub mod rules;
+pub mod stats;
+pub mod storage;
 
 pub mod consts {
     pub const STARTING_TREES: f32 = 0.50;
     pub const STARTING_JACKS: f32 = 0.10;
     pub const STARTING_BEARS: f32 = 0.02;
 
-    pub const NONE_MASK: u16 = 0x0000;
-    pub const TREE_MASK: u16 = 0x00FF;
-    pub const JACK_MASK: u16 = 0x0F00;
-    pub const BEAR_MASK: u16 = 0xF000;
+    use crate::cell::Cell;
 
-    pub const TREE_REMOVE_MASK: u16 = 0xFF00;
-    pub const JACK_REMOVE_MASK: u16 = 0xF0FF;
-    pub const BEAR_REMOVE_MASK: u16 = 0x0FFF;
+    pub const NONE_MASK: Cell = 0x0000_0000;
+    pub const TREE_MASK: Cell = 0x0000_0FFF;
+    pub const SPECIES_MASK: Cell = 0x0000_F000;
+    pub const JACK_MASK: Cell = 0x00FF_0000;
+    pub const BEAR_MASK: Cell = 0x0F00_0000;
+    pub const FLAGS_MASK: Cell = 0xF000_0000;
+    pub const ENTITY_MASK: Cell = TREE_MASK | JACK_MASK | BEAR_MASK;
 
-    pub const TREE_SHIFT: u16 = 4 * 0;
-    pub const JACK_SHIFT: u16 = 4 * 2;
-    pub const BEAR_SHIFT: u16 = 4 * 3;
+    pub const TREE_REMOVE_MASK: Cell = !(TREE_MASK | SPECIES_MASK);
+    pub const JACK_REMOVE_MASK: Cell = !JACK_MASK;
+    pub const BEAR_REMOVE_MASK: Cell = !BEAR_MASK;
+
+    pub const TREE_SHIFT: u32 = 0;
+    pub const SPECIES_SHIFT: u32 = 12;
+    pub const JACK_SHIFT: u32 = 16;
+    pub const BEAR_SHIFT: u32 = 24;
+    pub const FLAGS_SHIFT: u32 = 28;
+
+    pub const TREE_MAX_AGE: Cell = TREE_MASK >> TREE_SHIFT;
 
     pub const SAPLING_SPAWN_CHANCE: u32 = 0;
     pub const MATURE_SPAWN_CHANCE: u32 = 10;
@@ -31,101 +53,597 @@ pub mod consts {
     pub const MATURE_HARVEST_AMOUNT: u32 = 2;
     pub const ELDER_HARVEST_AMOUNT: u32 = 4;
 
-    pub const JACK_MAX_LEVEL: u16 = 5;
-    pub const JACK_MIN_MAUL_PROTECTION: u16 = 75;
+    pub const JACK_MAX_LEVEL: Cell = 5;
+    pub const JACK_MIN_MAUL_PROTECTION: Cell = 75;
 
-    pub const SAPLING_GROW_AGE: u16 = 12;
-    pub const MATURE_GROW_AGE: u16 = 120;
+    pub const SAPLING_GROW_AGE: Cell = 12;
+    pub const MATURE_GROW_AGE: Cell = 120;
 
     pub const BEAR_WANDERS_PER_MONTH: u32 = 3;
     pub const BEAR_WANDER_ATTEMPTS: u32 = 2;
+    pub const BEAR_SIGHT_RADIUS: usize = 4;
+    pub const BEAR_CHASE_STEPS: u32 = 5;
+    /// The most steps a parsed hunting strategy may give a bear in one month.
+    pub const MAX_CHASE_STEPS: u32 = 32;
 
     pub const JACK_WANDERS_PER_MONTH: u32 = 3;
     pub const JACK_WANDER_ATTEMPTS: u32 = 2;
+    pub const JACK_SIGHT_RADIUS: usize = 3;
+    /// The furthest a parsed movement strategy may look, which bounds the cells each
+    /// entity scans per step.
+    pub const MAX_SIGHT_RADIUS: usize = 32;
+
+    pub const BEAR_MATURE_AGE: u16 = 36;
+    pub const BEAR_MAX_AGE: u16 = 300;
+
+    pub const BEAR_STARTING_FOOD: u16 = 24;
+    pub const BEAR_CUB_FOOD: u16 = 12;
+    pub const BEAR_MAX_FOOD: u16 = 48;
+    pub const BEAR_MONTHLY_HUNGER: u16 = 1;
+    pub const BEAR_MAUL_FOOD: u16 = 24;
+    pub const BEAR_FORAGE_FOOD: u16 = 1;
+    pub const BEAR_REPRODUCE_FOOD: u16 = 36;
+    pub const BEAR_REPRODUCE_COST: u16 = 18;
+
+    pub const LUMBER_PRICE: u32 = 10;
+    pub const LUMBER_SALE_PERCENT: u32 = 50;
+
+    pub const JACK_BASE_WAGE: u32 = 10;
+    pub const JACK_LEVEL_WAGE: u32 = 5;
+    pub const JACK_HIRING_COST: u32 = 50;
+
+    pub const CHUNK_SIZE: usize = 4096;
+    /// The most cells a forest may have. Sparse maps this size are practical since empty
+    /// chunks are never allocated; anything larger is almost certainly a typo.
+    pub const MAX_CELLS: usize = 1 << 30;
+    /// The largest quota a parsed policy may hold a population to; no map has more cells.
+    pub const MAX_POLICY_QUOTA: usize = MAX_CELLS;
+    /// The largest ratio or percentage a parsed policy may scale the tree count by.
+    pub const MAX_POLICY_PERCENT: usize = 10_000;
 }
 
 pub mod forest {
+    use std::collections::BTreeMap;
+    use std::ops::Deref;
+
+    use crate::consts::MAX_CELLS;
+    use crate::economy::Economy;
+    use crate::cell::{self, Cell};
+    use crate::digest::Fnv64;
+    use crate::edit::{Brush, Edit, EditError};
+    use crate::error::ForestError;
+    use crate::inspect::{BearInfo, CellInfo, JackInfo, NeighbourCounts, TreeInfo};
+    use crate::invariants::{InvariantError, Violation};
+    use crate::policy::{Adjustment, PolicyContext};
+    use crate::positions::{EntityIndex, PositionSet};
     use crate::random::Random;
+    use crate::rules::Rules;
+    use crate::stats::YearlyStats;
+    use crate::storage::ChunkedMap;
+    #[cfg(feature = "parallel")]
+    use crate::{consts::CHUNK_SIZE, storage::MapWindow};
 
+    use crate::consts::{
+        BEAR_CUB_FOOD, BEAR_FORAGE_FOOD, BEAR_MATURE_AGE, BEAR_MAUL_FOOD, BEAR_MAX_AGE,
+        BEAR_MAX_FOOD, BEAR_MONTHLY_HUNGER, BEAR_REPRODUCE_COST, BEAR_REPRODUCE_FOOD,
+        BEAR_STARTING_FOOD,
+    };
     use crate::consts::{
         BEAR_MASK, BEAR_REMOVE_MASK, BEAR_SHIFT, BEAR_WANDERS_PER_MONTH, BEAR_WANDER_ATTEMPTS,
         ELDER_HARVEST_CHANCE, ELDER_SPAWN_CHANCE, JACK_MASK, JACK_MAX_LEVEL, JACK_REMOVE_MASK,
         JACK_SHIFT, JACK_WANDERS_PER_MONTH, JACK_WANDER_ATTEMPTS, MATURE_GROW_AGE,
-        MATURE_HARVEST_CHANCE, MATURE_SPAWN_CHANCE, NONE_MASK, SAPLING_GROW_AGE,
-        SAPLING_HARVEST_CHANCE, SAPLING_SPAWN_CHANCE, STARTING_BEARS, STARTING_JACKS,
-        STARTING_TREES, TREE_MASK, TREE_REMOVE_MASK, TREE_SHIFT, SAPLING_HARVEST_AMOUNT, MATURE_HARVEST_AMOUNT, ELDER_HARVEST_AMOUNT, NONE_HARVEST_AMOUNT, JACK_MIN_MAUL_PROTECTION,
+        MATURE_HARVEST_CHANCE, MATURE_SPAWN_CHANCE, SAPLING_GROW_AGE,
+        SAPLING_HARVEST_CHANCE, SAPLING_SPAWN_CHANCE, SPECIES_MASK, SPECIES_SHIFT, FLAGS_MASK,
+        FLAGS_SHIFT, TREE_MAX_AGE, TREE_MASK, ENTITY_MASK, TREE_REMOVE_MASK, TREE_SHIFT, SAPLING_HARVEST_AMOUNT, MATURE_HARVEST_AMOUNT, ELDER_HARVEST_AMOUNT, NONE_HARVEST_AMOUNT, JACK_MIN_MAUL_PROTECTION,
     };
 
-    enum TreeKind {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "lowercase"))]
+    pub enum TreeKind {
         None,
         Sapling,
         Mature,
         Elder,
     }
 
+    const ADJACENT_MOVEMENTS: [(isize, isize); 8] = [
+        (-1, -1),
+        (0, -1),
+        (1, -1),
+        (-1, 0),
+        (1, 0),
+        (-1, 1),
+        (0, 1),
+        (1, 1),
+    ];
+
+    /// The in-bounds neighbours of a cell, stored inline so looking them up never allocates.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Neighbours {
+        positions: [usize; 8],
+        len: usize,
+    }
+
+    impl Neighbours {
+        fn push(&mut self, index: usize) {
+            self.positions[self.len] = index;
+            self.len += 1;
+        }
+
+        pub fn retain<F>(&mut self, mut keep: F)
+        where
+            F: FnMut(usize) -> bool,
+        {
+            let mut len = 0;
+            for i in 0..self.len {
+                if keep(self.positions[i]) {
+                    self.positions[len] = self.positions[i];
+                    len += 1;
+                }
+            }
+            self.len = len;
+        }
+    }
+
+    impl Deref for Neighbours {
+        type Target = [usize];
+
+        fn deref(&self) -> &[usize] {
+            &self.positions[..self.len]
+        }
+    }
+
+    impl IntoIterator for Neighbours {
+        type Item = usize;
+        type IntoIter = std::iter::Take<std::array::IntoIter<usize, 8>>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.positions.into_iter().take(self.len)
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Phase {
+        Trees,
+        Jacks,
+        Bears,
+        BearLifecycle,
+        Yearly,
+    }
+
+    impl Phase {
+        pub const ALL: [Phase; 5] = [
+            Phase::Trees,
+            Phase::Jacks,
+            Phase::Bears,
+            Phase::BearLifecycle,
+            Phase::Yearly,
+        ];
+
+        pub fn name(&self) -> &'static str {
+            match self {
+                Phase::Trees => "trees",
+                Phase::Jacks => "jacks",
+                Phase::Bears => "bears",
+                Phase::BearLifecycle => "bear-lifecycle",
+                Phase::Yearly => "yearly",
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Bear {
+        pub age: u16,
+        pub food: u16,
+    }
+
+    impl Bear {
+        pub fn new() -> Self {
+            Self {
+                age: BEAR_MATURE_AGE,
+                food: BEAR_STARTING_FOOD,
+            }
+        }
+
+        pub fn cub() -> Self {
+            Self {
+                age: 0,
+                food: BEAR_CUB_FOOD,
+            }
+        }
+
+        fn eat(&mut self, food: u16) {
+            self.food = u16::min(self.food + food, BEAR_MAX_FOOD);
+        }
+    }
+
+    impl Default for Bear {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[derive(Clone)]
     pub struct Forest {
         rng: Random,
-        pub map: Vec<u16>,
+        bears: BTreeMap<usize, Bear>,
+        index: EntityIndex,
+        map: ChunkedMap,
+        /// Cells whose contents changed since the last `take_changes`.
+        dirty: PositionSet,
+        pub rules: Rules,
+        pub economy: Economy,
+        pub history: Vec<YearlyStats>,
         pub width: usize,
         pub height: usize,
         pub months_elapsed: u32,
         pub yearly_lumber: u32,
         pub yearly_mauls: u32,
+        pub yearly_bear_births: u32,
+        pub yearly_bear_deaths: u32,
     }
 
     impl Forest {
         pub fn new(seed: u64, width: usize, height: usize) -> Self {
-            let mut rng = Random::new(seed);
-            let mut map = vec![NONE_MASK; width * height];
+            Self::with_rules(seed, width, height, Rules::default())
+        }
 
-            Self::initialize_map(&mut rng, &mut map);
+        /// Panics if the arguments are rejected by `try_with_rules`.
+        pub fn with_rules(seed: u64, width: usize, height: usize, rules: Rules) -> Self {
+            Self::try_with_rules(seed, width, height, rules)
+                .unwrap_or_else(|error| panic!("invalid forest: {}", error))
+        }
 
-            Self {
-                rng,
-                map,
+        pub fn try_new(seed: u64, width: usize, height: usize) -> Result<Self, ForestError> {
+            Self::try_with_rules(seed, width, height, Rules::default())
+        }
+
+        pub fn try_with_rules(
+            seed: u64,
+            width: usize,
+            height: usize,
+            rules: Rules,
+        ) -> Result<Self, ForestError> {
+            if width == 0 || height == 0 {
+                return Err(ForestError::ZeroDimensions { width, height });
+            }
+
+            if width.checked_mul(height).is_none_or(|cells| cells > MAX_CELLS) {
+                return Err(ForestError::TooLarge { width, height });
+            }
+
+            if !rules.density.is_valid() {
+                return Err(ForestError::InvalidDensity { density: rules.density });
+            }
+
+            if seed == 0 {
+                return Err(ForestError::DegenerateSeed);
+            }
+
+            let mut forest = Self {
+                rng: Random::new(seed),
+                bears: BTreeMap::new(),
+                index: EntityIndex::new(width * height),
+                map: ChunkedMap::new(width * height),
+                dirty: PositionSet::new(width * height),
+                rules,
+                economy: Economy::new(),
+                history: vec![],
                 width,
                 height,
                 months_elapsed: 0,
                 yearly_lumber: 0,
                 yearly_mauls: 0,
+                yearly_bear_births: 0,
+                yearly_bear_deaths: 0,
+            };
+
+            forest.initialize_map();
+
+            Ok(forest)
+        }
+
+        /// Swaps the rules of a running forest, taking effect from the next update. The
+        /// density is only used when a forest is created but is still checked, so the
+        /// rules can always be used to start another.
+        pub fn set_rules(&mut self, rules: Rules) -> Result<(), ForestError> {
+            if !rules.density.is_valid() {
+                return Err(ForestError::InvalidDensity { density: rules.density });
+            }
+
+            self.rules = rules;
+            Ok(())
+        }
+
+        pub fn cell(&self, index: usize) -> Cell {
+            self.map[index]
+        }
+
+        pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+            self.map.iter()
+        }
+
+        /// Every cell packed into the original `u16` layout, for consumers that predate
+        /// the wider cell format.
+        pub fn legacy_cells(&self) -> impl Iterator<Item = u16> + '_ {
+            self.map.iter().map(cell::to_legacy)
+        }
+
+        /// A digest of every cell's contents, stable across platforms and releases, for
+        /// checking that two runs reached the same map.
+        pub fn map_hash(&self) -> u64 {
+            let mut hasher = Fnv64::new();
+            hasher.write_u64(self.width as u64);
+            hasher.write_u64(self.height as u64);
+
+            for (index, cell) in self.map.occupied() {
+                hasher.write_u64(index as u64);
+                hasher.write_u32(cell);
+            }
+
+            hasher.finish()
+        }
+
+        /// A digest of everything that decides how the forest evolves from here: the map,
+        /// the counters, each bear's age and food, the economy and the random generator.
+        /// Two forests with the same state hash will produce the same future.
+        pub fn state_hash(&self) -> u64 {
+            let mut hasher = Fnv64::new();
+            hasher.write_u64(self.map_hash());
+            hasher.write_u64(self.rng.state());
+            hasher.write_u32(self.months_elapsed);
+            hasher.write_u32(self.yearly_lumber);
+            hasher.write_u32(self.yearly_mauls);
+            hasher.write_u32(self.yearly_bear_births);
+            hasher.write_u32(self.yearly_bear_deaths);
+            // Only stockpiles past `u32::MAX` hash their high half, so digests recorded
+            // before the stockpile was widened still match.
+            hasher.write_u32(self.economy.stockpile as u32);
+            if self.economy.stockpile > u32::MAX as u64 {
+                hasher.write_u32((self.economy.stockpile >> 32) as u32);
+            }
+            hasher.write_u64(self.economy.funds as u64);
+
+            for (&index, bear) in &self.bears {
+                hasher.write_u64(index as u64);
+                hasher.write_u32(bear.age as u32);
+                hasher.write_u32(bear.food as u32);
             }
+
+            hasher.finish()
+        }
+
+        pub fn positions(&self) -> &EntityIndex {
+            &self.index
+        }
+
+        /// The indices of cells changed since the last `take_changes`, in ascending order.
+        pub fn dirty_cells(&self) -> impl Iterator<Item = usize> + '_ {
+            self.dirty.iter()
+        }
+
+        /// Returns every cell changed since the previous call with its current contents, and
+        /// starts tracking afresh. A new forest reports all of its initially placed cells.
+        pub fn take_changes(&mut self) -> Vec<(usize, Cell)> {
+            let changes = self.dirty.iter().map(|index| (index, self.map[index])).collect();
+            self.dirty.clear();
+            changes
+        }
+
+        /// The number of map chunks holding at least one tree, jack or bear.
+        pub fn active_chunks(&self) -> usize {
+            self.map.active_chunk_count()
+        }
+
+        /// Roughly how many bytes a clone of the forest takes up, for callers that keep
+        /// copies around. Bears are counted at their entry size, ignoring tree nodes.
+        pub fn heap_size(&self) -> usize {
+            std::mem::size_of::<Self>()
+                + self.map.heap_size()
+                + self.index.heap_size()
+                + self.dirty.heap_size()
+                + self.bears.len() * std::mem::size_of::<(usize, Bear)>()
+                + self.history.capacity() * std::mem::size_of::<YearlyStats>()
+        }
+
+        pub fn tree_count(&self) -> usize {
+            self.index.trees.len()
+        }
+
+        pub fn jack_count(&self) -> usize {
+            self.index.jacks.len()
+        }
+
+        pub fn bear_count(&self) -> usize {
+            self.index.bears.len()
+        }
+
+        pub fn bear_at(&self, index: usize) -> Option<&Bear> {
+            self.bears.get(&index)
+        }
+
+        pub fn tree_kind_at(&self, index: usize) -> TreeKind {
+            Self::get_tree_kind(self.map[index])
+        }
+
+        pub fn species_at(&self, index: usize) -> Cell {
+            (self.map[index] & SPECIES_MASK) >> SPECIES_SHIFT
+        }
+
+        pub fn flags_at(&self, index: usize) -> Cell {
+            (self.map[index] & FLAGS_MASK) >> FLAGS_SHIFT
+        }
+
+        /// Describes the cell at `(x, y)`, or `None` if it is off the map.
+        pub fn inspect(&self, x: usize, y: usize) -> Option<CellInfo> {
+            if x >= self.width || y >= self.height {
+                return None;
+            }
+
+            let index = self.position_to_index(x, y);
+            let cell = self.map[index];
+
+            let tree = (cell & TREE_MASK != 0).then(|| TreeInfo {
+                kind: Self::get_tree_kind(cell),
+                age: (cell & TREE_MASK) >> TREE_SHIFT,
+                species: (cell & SPECIES_MASK) >> SPECIES_SHIFT,
+                spawn_chance: Self::get_sapling_spawn_chance(cell),
+                harvest_chance: Self::get_tree_harvest_chance(cell),
+                harvest_amount: Self::get_harvest_amount(cell),
+            });
+
+            let jack = (cell & JACK_MASK != 0).then(|| JackInfo {
+                level: (cell & JACK_MASK) >> JACK_SHIFT,
+                maul_chance: Self::get_jack_maul_chance(cell),
+            });
+
+            let bear = (cell & BEAR_MASK != 0).then(|| BearInfo {
+                count: (cell & BEAR_MASK) >> BEAR_SHIFT,
+                lifecycle: self.bears.get(&index).copied().filter(|_| self.rules.bear_lifecycle),
+            });
+
+            let mut neighbours = NeighbourCounts::default();
+            for position in self.get_adjacent_positions(index) {
+                let cell = self.map[position];
+                neighbours.cells += 1;
+                neighbours.trees += (cell & TREE_MASK != 0) as usize;
+                neighbours.jacks += (cell & JACK_MASK != 0) as usize;
+                neighbours.bears += (cell & BEAR_MASK != 0) as usize;
+            }
+
+            Some(CellInfo {
+                index,
+                position: (x, y),
+                cell,
+                tree,
+                jack,
+                bear,
+                flags: (cell & FLAGS_MASK) >> FLAGS_SHIFT,
+                neighbours,
+            })
+        }
+
+        /// Applies `edit` to every cell under `brush` and returns how many cells changed.
+        /// Nothing is changed if the edit or brush is invalid.
+        pub fn paint(&mut self, brush: Brush, edit: Edit) -> Result<usize, EditError> {
+            edit.validate()?;
+            let indices = brush.indices(self.width, self.height)?;
+
+            let mut changed = 0;
+            for index in indices {
+                let before = self.map[index];
+                self.apply_edit(index, edit);
+                changed += (self.map[index] != before) as usize;
+            }
+
+            Ok(changed)
+        }
+
+        /// Plants a tree of `age` at `(x, y)`. A tree already there keeps its species.
+        pub fn set_tree(&mut self, x: usize, y: usize, age: Cell) -> Result<(), EditError> {
+            let species = match x < self.width && y < self.height {
+                true => self.species_at(self.position_to_index(x, y)),
+                false => 0,
+            };
+            self.paint(Brush::Cell { x, y }, Edit::SetTree { age, species })
+                .map(|_| ())
+        }
+
+        pub fn clear_tree(&mut self, x: usize, y: usize) -> Result<(), EditError> {
+            self.paint(Brush::Cell { x, y }, Edit::ClearTree).map(|_| ())
+        }
+
+        pub fn place_jack(&mut self, x: usize, y: usize, level: Cell) -> Result<(), EditError> {
+            self.paint(Brush::Cell { x, y }, Edit::PlaceJack { level })
+                .map(|_| ())
         }
 
-        fn initialize_map(rng: &mut Random, map: &mut [u16]) {
-            let num_bears = f32::ceil(map.len() as f32 * STARTING_BEARS) as usize;
+        pub fn remove_jack(&mut self, x: usize, y: usize) -> Result<(), EditError> {
+            self.paint(Brush::Cell { x, y }, Edit::RemoveJack).map(|_| ())
+        }
+
+        pub fn place_bear(&mut self, x: usize, y: usize) -> Result<(), EditError> {
+            self.paint(Brush::Cell { x, y }, Edit::PlaceBear).map(|_| ())
+        }
+
+        pub fn remove_bear(&mut self, x: usize, y: usize) -> Result<(), EditError> {
+            self.paint(Brush::Cell { x, y }, Edit::RemoveBear).map(|_| ())
+        }
+
+        fn apply_edit(&mut self, index: usize, edit: Edit) {
+            let cell = self.map[index];
+
+            match edit {
+                Edit::SetTree { age, species } => self.set_cell(
+                    index,
+                    (cell & TREE_REMOVE_MASK) | (age << TREE_SHIFT) | (species << SPECIES_SHIFT),
+                ),
+                Edit::ClearTree => self.remove_entity(index, TREE_REMOVE_MASK),
+                Edit::PlaceJack { level } => {
+                    self.set_cell(index, (cell & JACK_REMOVE_MASK) | (level << JACK_SHIFT))
+                }
+                Edit::RemoveJack => self.remove_entity(index, JACK_REMOVE_MASK),
+                Edit::PlaceBear if cell & BEAR_MASK == 0 => self.spawn_bear(index, Bear::new()),
+                Edit::PlaceBear => {}
+                Edit::RemoveBear if cell & BEAR_MASK != 0 => self.kill_bear(index),
+                Edit::RemoveBear => {}
+            }
+        }
+
+        pub fn index_to_position(&self, index: usize) -> (usize, usize) {
+            Self::convert_index_to_position(index, self.width)
+        }
+
+        pub fn position_to_index(&self, x: usize, y: usize) -> usize {
+            Self::convert_position_to_index(x, y, self.width)
+        }
+
+        fn initialize_map(&mut self) {
+            let num_bears = f32::ceil(self.map.len() as f32 * self.rules.density.bears) as usize;
             for n in 0..num_bears {
-                Self::randomly_place_entity(rng, map, n, BEAR_MASK, BEAR_SHIFT);
+                if let Some(index) = self.randomly_place_entity(n, BEAR_MASK, BEAR_SHIFT) {
+                    self.bears.insert(index, Bear::new());
+                }
             }
 
-            let num_jacks = f32::ceil(map.len() as f32 * STARTING_JACKS) as usize;
+            let num_jacks = f32::ceil(self.map.len() as f32 * self.rules.density.jacks) as usize;
             for n in 0..num_jacks {
-                Self::randomly_place_entity(rng, map, n, JACK_MASK, JACK_SHIFT);
+                self.randomly_place_entity(n, JACK_MASK, JACK_SHIFT);
             }
 
-            let num_trees = f32::ceil(map.len() as f32 * STARTING_TREES) as usize;
+            let num_trees = f32::ceil(self.map.len() as f32 * self.rules.density.trees) as usize;
             for n in 0..num_trees {
-                Self::randomly_place_entity(rng, map, n, TREE_MASK, TREE_SHIFT);
+                self.randomly_place_entity(n, TREE_MASK, TREE_SHIFT);
             }
         }
 
-        fn randomly_place_entity(rng: &mut Random, map: &mut [u16], num_ents: usize, mask: u16, shift: u16) {
-            if num_ents == map.len() {
-                return;
+        fn randomly_place_entity(&mut self, num_ents: usize, mask: Cell, shift: u32) -> Option<usize> {
+            if num_ents == self.map.len() {
+                return None;
             }
 
             loop {
-                let next = rng.next() as usize % map.len();
-                let cell = map[next];
+                let next = self.rng.next() as usize % self.map.len();
+                let cell = self.map[next];
 
                 if ((cell & mask) >> shift) == 0 {
-                    Self::place_entity(map, next, shift);
-                    break;
+                    self.place_entity(next, shift);
+                    return Some(next);
                 }
             }
         }
 
-        fn place_entity(map: &mut [u16], index: usize, shift: u16) {
-            map[index] += 0x1 << shift;
+        fn set_cell(&mut self, index: usize, cell: Cell) {
+            let old = self.map.set(index, cell);
+            self.index.update(index, old, cell);
+
+            if old != cell {
+                self.dirty.insert(index);
+            }
+        }
+
+        fn place_entity(&mut self, index: usize, shift: u32) {
+            self.set_cell(index, self.map[index] + (0x1 << shift));
         }
 
         pub fn draw_map(&self) {
@@ -158,7 +676,7 @@ pub mod forest {
             println!();
         }
 
-        fn get_tree_kind(cell: u16) -> TreeKind {
+        fn get_tree_kind(cell: Cell) -> TreeKind {
             match cell & TREE_MASK {
                 0 => TreeKind::None,
                 age => {
@@ -174,81 +692,71 @@ pub mod forest {
         }
 
         fn trigger_tree_event(&mut self) {
-            let positions = Self::get_entity_positions(&self.map, TREE_MASK, TREE_SHIFT);
+            #[cfg(feature = "parallel")]
+            if self.rules.tree_threads > 1 {
+                self.trigger_parallel_tree_event(self.rules.tree_threads);
+                return;
+            }
+
+            let positions = self.index.trees.to_vec();
             for i in positions {
                 let cell = self.map[i];
 
-                Self::age_tree(&mut self.map, i, cell);
+                self.age_tree(i, cell);
 
                 let spawn_chance = Self::get_sapling_spawn_chance(cell);
                 let result = self.rng.next() as u32 % 100;
 
                 if result <= spawn_chance {
-                    let adjacent_positions = self.get_adjacent_positions(i);
-                    let mut position_candidates = adjacent_positions
-                        .iter()
-                        .filter(|&position| {
-                            let cell = self.map[*position];
-                            cell & TREE_MASK == 0
-                        })
-                        .collect();
-
-                    if let Some(&choice) = self.rng.choose(&mut position_candidates) {
-                        Self::place_entity(&mut self.map, choice, TREE_SHIFT)
+                    let mut position_candidates = self.get_adjacent_positions(i);
+                    position_candidates.retain(|position| {
+                        let cell = self.map[position];
+                        cell & TREE_MASK == 0
+                    });
+
+                    if let Some(choice) = self.rng.choose(&position_candidates) {
+                        self.set_cell(choice, Self::seeded_cell(self.map[choice], cell));
                     }
                 }
             }
         }
 
-        fn age_tree(map: &mut [u16], index: usize, cell: u16) {
-            let tree_age = cell & TREE_MASK;
-            if tree_age < 255 {
-                map[index] += 0x1;
-            }
+        fn age_tree(&mut self, index: usize, cell: Cell) {
+            self.set_cell(index, Self::aged_tree(cell));
         }
 
-        fn get_entity_positions(map: &[u16], mask: u16, shift: u16) -> Vec<usize> {
-            (0..map.len())
-                .into_iter()
-                .filter(|&i| ((map[i] & mask) >> shift) > 0)
-                .collect::<Vec<usize>>()
+        fn aged_tree(cell: Cell) -> Cell {
+            let tree_age = (cell & TREE_MASK) >> TREE_SHIFT;
+            if tree_age < TREE_MAX_AGE {
+                cell + 0x1
+            } else {
+                cell
+            }
         }
 
-        fn get_adjacent_positions(&self, index: usize) -> Vec<usize> {
-            let mut positions: Vec<usize> = vec![];
+        /// A sapling of the parent's species added to `cell`.
+        fn seeded_cell(cell: Cell, parent: Cell) -> Cell {
+            cell + (0x1 << TREE_SHIFT) + (parent & SPECIES_MASK)
+        }
 
-            let adjacent_movements: Vec<(isize, isize)> = vec![
-                (-1, -1),
-                (0, -1),
-                (1, -1),
-                (-1, 0),
-                (1, 0),
-                (-1, 1),
-                (0, 1),
-                (1, 1),
-            ];
+        pub fn get_adjacent_positions(&self, index: usize) -> Neighbours {
+            Self::adjacent_positions(index, self.width, self.height)
+        }
 
-            let x = (index % self.width) as usize;
-            let y = (index / self.width) as usize;
+        fn adjacent_positions(index: usize, width: usize, height: usize) -> Neighbours {
+            let mut positions = Neighbours::default();
+            let (x, y) = Self::convert_index_to_position(index, width);
 
-            for movement in adjacent_movements {
-                let x = x as isize + movement.0;
-                let y = y as isize + movement.1;
-                if x < 0 || y < 0 {
+            for (dx, dy) in ADJACENT_MOVEMENTS {
+                let (Some(x), Some(y)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
                     continue;
-                }
+                };
 
-                let x = x as usize;
-                let y = y as usize;
-                if x >= self.width || y >= self.height {
+                if x >= width || y >= height {
                     continue;
                 }
 
-                positions.push(Self::convert_position_to_index(
-                    x as usize,
-                    y as usize,
-                    self.width,
-                ));
+                positions.push(Self::convert_position_to_index(x, y, width));
             }
 
             positions
@@ -262,7 +770,7 @@ pub mod forest {
             y * width + x
         }
 
-        fn get_sapling_spawn_chance(cell: u16) -> u32 {
+        fn get_sapling_spawn_chance(cell: Cell) -> u32 {
             let kind = Self::get_tree_kind(cell);
             match kind {
                 TreeKind::Sapling => SAPLING_SPAWN_CHANCE,
@@ -273,7 +781,8 @@ pub mod forest {
         }
 
         fn trigger_jack_event(&mut self) {
-            let positions = Self::get_entity_positions(&self.map, JACK_MASK, JACK_SHIFT);
+            let movement = self.rules.jack_movement.build();
+            let positions = self.index.jacks.to_vec();
             for i in positions {
                 let mut wanders = 0;
                 let mut current_position = i;
@@ -282,24 +791,23 @@ pub mod forest {
                     let mut has_wandered = false;
                     let mut wander_attempts = 0;
 
-                    let adjacent_positions = self.get_adjacent_positions(current_position);
-                    let mut position_candidates: Vec<&usize> = adjacent_positions
-                        .iter()
-                        .filter(|&position| {
-                            let cell = self.map[*position];
-                            (cell & JACK_MASK) == 0
-                        })
-                        .collect();
+                    let mut position_candidates = self.get_adjacent_positions(current_position);
+                    position_candidates.retain(|position| {
+                        let cell = self.map[position];
+                        (cell & JACK_MASK) == 0
+                    });
+
+                    movement.filter_steps(self, current_position, &mut position_candidates);
 
-                    if position_candidates.len() == 0 {
+                    if position_candidates.is_empty() {
                         break;
                     }
 
                     while wander_attempts < JACK_WANDER_ATTEMPTS && !has_wandered {
-                        match self.rng.choose(&mut position_candidates) {
-                            Some(&next_position) => {
-                                Self::remove_entity(&mut self.map, current_position, JACK_REMOVE_MASK);
-                                Self::place_entity(&mut self.map, next_position, JACK_SHIFT);
+                        match self.rng.choose(&position_candidates) {
+                            Some(next_position) => {
+                                self.remove_entity(current_position, JACK_REMOVE_MASK);
+                                self.place_entity(next_position, JACK_SHIFT);
 
                                 let chosen_cell = self.map[next_position];
                                 if (chosen_cell & TREE_MASK) > 0 {
@@ -307,12 +815,15 @@ pub mod forest {
                                     if result < Self::get_tree_harvest_chance(chosen_cell) {
                                         let harvest_amount = Self::get_harvest_amount(chosen_cell);
                                         self.yearly_lumber += harvest_amount;
-                                        Self::remove_entity(&mut self.map, next_position, TREE_REMOVE_MASK);
-                                        Self::level_up_jack(&mut self.map, next_position, harvest_amount);
+                                        if self.rules.economy {
+                                            self.economy.store(harvest_amount);
+                                        }
+                                        self.remove_entity(next_position, TREE_REMOVE_MASK);
+                                        self.level_up_jack(next_position, harvest_amount);
                                     } else {
                                         // let harvest_amount = Self::get_harvest_amount(chosen_cell) / 2;
                                         // self.yearly_lumber += harvest_amount;
-                                        Self::de_age_tree(&mut self.map, next_position);
+                                        self.de_age_tree(next_position);
                                         // Self::level_up_jack(map, next_position, harvest_amount);
                                     }
 
@@ -337,7 +848,7 @@ pub mod forest {
             }
         }
 
-        fn get_tree_harvest_chance(cell: u16) -> u32 {
+        fn get_tree_harvest_chance(cell: Cell) -> u32 {
             match Self::get_tree_kind(cell) {
                 TreeKind::Sapling => SAPLING_HARVEST_CHANCE,
                 TreeKind::Mature => MATURE_HARVEST_CHANCE,
@@ -346,39 +857,38 @@ pub mod forest {
             }
         }
 
-        fn de_age_tree(map: &mut [u16], index: usize) {
-            let cell = map[index];
+        fn de_age_tree(&mut self, index: usize) {
+            let cell = self.map[index];
 
             match Self::get_tree_kind(cell) {
                 TreeKind::Sapling => {
-                    map[index] -= (cell & TREE_MASK) - 1;
+                    self.set_cell(index, cell - ((cell & TREE_MASK) - 1));
                 }
                 TreeKind::Mature => {
-                    map[index] -= (cell & TREE_MASK) - SAPLING_GROW_AGE;
+                    self.set_cell(index, cell - ((cell & TREE_MASK) - SAPLING_GROW_AGE));
                 }
                 TreeKind::Elder => {
-                    map[index] -= (cell & TREE_MASK) - MATURE_GROW_AGE;
+                    self.set_cell(index, cell - ((cell & TREE_MASK) - MATURE_GROW_AGE));
                 }
-                TreeKind::None => return,
+                TreeKind::None => {}
             }
         }
 
-        fn level_up_jack(map: &mut [u16], index: usize, lumber: u32) {
-            let cell = map[index];
+        fn level_up_jack(&mut self, index: usize, lumber: u32) {
+            let cell = self.map[index];
             let current_level = (cell & JACK_MASK) >> JACK_SHIFT;
 
             if current_level <= JACK_MAX_LEVEL {
-                let level = u16::min(current_level + lumber as u16, JACK_MAX_LEVEL);
-                map[index] &= JACK_REMOVE_MASK;
-                map[index] += level << JACK_SHIFT;
+                let level = Cell::min(current_level + lumber as Cell, JACK_MAX_LEVEL);
+                self.set_cell(index, (cell & JACK_REMOVE_MASK) + (level << JACK_SHIFT));
             }
         }
 
-        fn remove_entity(map: &mut [u16], index: usize, remove_mask: u16) {
-            map[index] &= remove_mask;
+        fn remove_entity(&mut self, index: usize, remove_mask: Cell) {
+            self.set_cell(index, self.map[index] & remove_mask);
         }
 
-        fn get_harvest_amount(cell: u16) -> u32 {
+        fn get_harvest_amount(cell: Cell) -> u32 {
             match Self::get_tree_kind(cell) {
                 TreeKind::None => NONE_HARVEST_AMOUNT,
                 TreeKind::Sapling => SAPLING_HARVEST_AMOUNT,
@@ -388,46 +898,51 @@ pub mod forest {
         }
 
         fn trigger_bear_event(&mut self) {
-            let positions = Self::get_entity_positions(&self.map, BEAR_MASK, BEAR_SHIFT);
+            let movement = self.rules.bear_movement.build();
+            let positions = self.index.bears.to_vec();
             for i in positions {
                 let mut wanders = 0;
                 let mut current_position = i;
+                let steps = movement.steps_per_month(self, i, BEAR_WANDERS_PER_MONTH);
 
-                while wanders < BEAR_WANDERS_PER_MONTH {
+                while wanders < steps {
                     let mut has_wandered = false;
                     let mut wander_attempts = 0;
 
-                    let adjacent_positions = self.get_adjacent_positions(current_position);
-                    let mut position_candidates: Vec<&usize> = adjacent_positions
-                        .iter()
-                        .filter(|&position| {
-                            let cell = self.map[*position];
-                            (cell & BEAR_MASK) == 0
-                        })
-                        .collect();
+                    let mut position_candidates = self.get_adjacent_positions(current_position);
+                    position_candidates.retain(|position| {
+                        let cell = self.map[position];
+                        (cell & BEAR_MASK) == 0
+                    });
+
+                    movement.filter_steps(self, current_position, &mut position_candidates);
 
-                    if position_candidates.len() == 0 {
+                    if position_candidates.is_empty() {
                         break;
                     }
 
                     while wander_attempts < BEAR_WANDER_ATTEMPTS && !has_wandered {
-                        match self.rng.choose(&mut position_candidates) {
-                            Some(&next_position) => {
-                                Self::remove_entity(&mut self.map, current_position, BEAR_REMOVE_MASK);
-                                Self::place_entity(&mut self.map, next_position, BEAR_SHIFT);
+                        match self.rng.choose(&position_candidates) {
+                            Some(next_position) => {
+                                self.move_bear(current_position, next_position);
 
                                 let chosen_cell = self.map[next_position];
                                 if (chosen_cell & JACK_MASK) > 0 {
                                     let result = self.rng.next() as u32 % 100;
                                     if result < Self::get_jack_maul_chance(chosen_cell) {
                                         self.yearly_mauls += 1;
-                                        Self::remove_entity(&mut self.map, next_position, JACK_REMOVE_MASK);
+                                        self.remove_entity(next_position, JACK_REMOVE_MASK);
+                                        self.feed_bear(next_position, BEAR_MAUL_FOOD);
                                     } else {
-                                        Self::de_level_jack(&mut self.map, next_position);
+                                        self.de_level_jack(next_position);
                                     }
 
-                                    wanders = BEAR_WANDERS_PER_MONTH;
+                                    wanders = steps;
                                 } else {
+                                    if let TreeKind::Elder = Self::get_tree_kind(chosen_cell) {
+                                        self.feed_bear(next_position, BEAR_FORAGE_FOOD);
+                                    }
+
                                     wanders += 1;
                                 }
 
@@ -447,86 +962,445 @@ pub mod forest {
             }
         }
 
-        fn get_jack_maul_chance(cell: u16) -> u32 {
+        fn move_bear(&mut self, from: usize, to: usize) {
+            self.remove_entity(from, BEAR_REMOVE_MASK);
+            self.place_entity(to, BEAR_SHIFT);
+
+            let bear = self.bears.remove(&from).unwrap_or_default();
+            self.bears.insert(to, bear);
+        }
+
+        fn spawn_bear(&mut self, index: usize, bear: Bear) {
+            self.place_entity(index, BEAR_SHIFT);
+            self.bears.insert(index, bear);
+        }
+
+        fn kill_bear(&mut self, index: usize) {
+            self.remove_entity(index, BEAR_REMOVE_MASK);
+            self.bears.remove(&index);
+        }
+
+        fn feed_bear(&mut self, index: usize, food: u16) {
+            if !self.rules.bear_lifecycle {
+                return;
+            }
+
+            if let Some(bear) = self.bears.get_mut(&index) {
+                bear.eat(food);
+            }
+        }
+
+        fn trigger_bear_lifecycle_event(&mut self) {
+            let positions = self.index.bears.to_vec();
+            for i in positions {
+                let mut bear = self.bears.get(&i).copied().unwrap_or_default();
+
+                bear.age += 1;
+                bear.food = bear.food.saturating_sub(BEAR_MONTHLY_HUNGER);
+
+                if bear.food == 0 || bear.age >= BEAR_MAX_AGE {
+                    self.kill_bear(i);
+                    self.yearly_bear_deaths += 1;
+                    continue;
+                }
+
+                if bear.age >= BEAR_MATURE_AGE && bear.food >= BEAR_REPRODUCE_FOOD {
+                    let mut position_candidates = self.get_adjacent_positions(i);
+                    position_candidates.retain(|position| (self.map[position] & BEAR_MASK) == 0);
+
+                    if let Some(cub_position) = self.rng.choose(&position_candidates) {
+                        self.spawn_bear(cub_position, Bear::cub());
+                        self.yearly_bear_births += 1;
+                        bear.food -= BEAR_REPRODUCE_COST;
+                    }
+                }
+
+                self.bears.insert(i, bear);
+            }
+        }
+
+        fn get_jack_maul_chance(cell: Cell) -> u32 {
             let level = (cell & JACK_MASK) >> JACK_SHIFT;
             let base_maul_protection = level * 10;
-            let low_level_protection_bonus = 10 - u16::min(level.pow(2), 10);
+            let low_level_protection_bonus = 10 - Cell::min(level.saturating_pow(2), 10);
             let maul_protection = base_maul_protection + low_level_protection_bonus;
-            let maul_chance = 100 - u16::min(maul_protection, JACK_MIN_MAUL_PROTECTION);
-            maul_chance as u32
+            100 - Cell::min(maul_protection, JACK_MIN_MAUL_PROTECTION)
         }
 
-        fn de_level_jack(map: &mut [u16], index: usize) {
-            let cell = map[index];
+        fn de_level_jack(&mut self, index: usize) {
+            let cell = self.map[index];
             let level = (cell & JACK_MASK) >> JACK_SHIFT;
 
             if level > 1 {
-                map[index] &= JACK_REMOVE_MASK;
-                map[index] += (level - 1) << JACK_SHIFT;
+                self.set_cell(index, (cell & JACK_REMOVE_MASK) + ((level - 1) << JACK_SHIFT));
             }
         }
 
         fn trigger_yearly_events(&mut self) {
-            {
-                let jacks = Self::get_entity_positions(&self.map, JACK_MASK, JACK_SHIFT);
-                if self.yearly_lumber as usize > jacks.len() {
-                    let excess_lumber = self.yearly_lumber as usize - jacks.len();
-                    let new_jacks = excess_lumber / 10;
-
-                    for _ in 0..new_jacks {
-                        if let Some(index) = Self::get_open_space(&mut self.rng, &mut self.map) {
-                            Self::place_entity(&mut self.map, index, JACK_SHIFT);
+            let mut stats = YearlyStats::new(self.months_elapsed / 12);
+            let jacks = self.index.jacks.to_vec();
+            let bears = self.index.bears.to_vec();
+
+            let mut context = PolicyContext {
+                year: stats.year,
+                cells: self.map.len(),
+                trees: self.index.trees.len(),
+                jacks: jacks.len(),
+                bears: bears.len(),
+                lumber: self.yearly_lumber,
+                mauls: self.yearly_mauls,
+                ..PolicyContext::default()
+            };
+
+            if self.rules.economy {
+                let wages: u64 = jacks
+                    .iter()
+                    .map(|&i| Economy::jack_wage((self.map[i] & JACK_MASK) >> JACK_SHIFT))
+                    .sum();
+
+                let accounts = self.economy.close_year(wages);
+                stats.lumber_sold = accounts.lumber_sold;
+                stats.revenue = accounts.revenue;
+                stats.wages = accounts.wages;
+                stats.profit = accounts.profit;
+
+                context.profit = Some(accounts.profit);
+                context.wages = accounts.wages;
+                context.affordable_hires = self.economy.affordable_hires(accounts.profit);
+            }
+
+            let policy = self.rules.policy.build();
+
+            match policy.adjust_jacks(&context) {
+                Adjustment::Hire(amount) => {
+                    for _ in 0..amount {
+                        let Some(index) = self.get_open_space() else {
+                            break;
+                        };
+                        self.place_entity(index, JACK_SHIFT);
+                        if self.rules.economy {
+                            self.economy.pay_hiring_cost();
                         }
+                        stats.jacks_hired += 1;
                     }
-                } else {
-                    if jacks.len() > 1 {
-                        if let Some(index) = self.rng.choose(&jacks) {
-                            Self::remove_entity(&mut self.map, index, JACK_REMOVE_MASK);
-                        }
+                }
+                Adjustment::Cull(amount) => {
+                    for index in self.choose_many(jacks, amount) {
+                        self.remove_entity(index, JACK_REMOVE_MASK);
+                        stats.jacks_fired += 1;
                     }
                 }
+                Adjustment::None => {}
             }
 
-            {
-                let bears = Self::get_entity_positions(&self.map, BEAR_MASK, BEAR_SHIFT);
-                if self.yearly_mauls as usize == 0 {
-                    if let Some(index) = Self::get_open_space(&mut self.rng, &mut self.map) {
-                        Self::place_entity(&mut self.map, index, BEAR_SHIFT);
+            if !self.rules.bear_lifecycle {
+                match policy.adjust_bears(&context) {
+                    Adjustment::Hire(amount) => {
+                        for _ in 0..amount {
+                            let Some(index) = self.get_open_space() else {
+                                break;
+                            };
+                            self.spawn_bear(index, Bear::new());
+                        }
                     }
-                } else {
-                    if bears.len() > 1 {
-                        if let Some(index) = self.rng.choose(&bears) {
-                            Self::remove_entity(&mut self.map, index, BEAR_REMOVE_MASK);
+                    Adjustment::Cull(amount) => {
+                        for index in self.choose_many(bears, amount) {
+                            self.kill_bear(index);
                         }
                     }
+                    Adjustment::None => {}
                 }
             }
 
+            self.record_yearly_stats(stats);
+
             self.yearly_lumber = 0;
             self.yearly_mauls = 0;
+            self.yearly_bear_births = 0;
+            self.yearly_bear_deaths = 0;
         }
 
-        fn get_open_space(rng: &mut Random, map: &[u16]) -> Option<usize> {
-            let mut spaces: Vec<usize> = vec![];
-            for i in 0..map.len() {
-                let cell = map[i];
-                if cell > 0 {
-                    continue;
-                }
-                spaces.push(i);
+        fn choose_many(&mut self, mut positions: Vec<usize>, amount: usize) -> Vec<usize> {
+            let mut chosen = vec![];
+            while chosen.len() < amount && !positions.is_empty() {
+                let next = self.rng.next() as usize % positions.len();
+                chosen.push(positions.swap_remove(next));
             }
-            rng.choose(&spaces)
+            chosen
+        }
+
+        fn record_yearly_stats(&mut self, mut stats: YearlyStats) {
+            stats.trees = self.index.trees.len() as u32;
+            stats.jacks = self.index.jacks.len() as u32;
+            stats.bears = self.index.bears.len() as u32;
+            stats.lumber = self.yearly_lumber;
+            stats.mauls = self.yearly_mauls;
+            stats.bear_births = self.yearly_bear_births;
+            stats.bear_deaths = self.yearly_bear_deaths;
+            stats.stockpile = self.economy.stockpile;
+            stats.funds = self.economy.funds;
+
+            self.history.push(stats);
+        }
+
+        fn get_open_space(&mut self) -> Option<usize> {
+            let spaces = self.index.empty_len();
+            if spaces == 0 {
+                return None;
+            }
+
+            let next = self.rng.next() as usize % spaces;
+            self.index.nth_empty(next)
         }
 
         pub fn update(&mut self) {
+            if cfg!(feature = "debug-invariants") {
+                if let Err(error) = self.checked_update() {
+                    panic!("forest invariant broken: {}", error);
+                }
+                return;
+            }
+
             self.months_elapsed += 1;
 
-            self.trigger_tree_event();
-            self.trigger_jack_event();
-            self.trigger_bear_event();
+            for phase in self.monthly_phases() {
+                self.run_phase(phase);
+            }
+        }
 
-            if self.months_elapsed % 12 == 0 {
-                self.trigger_yearly_events();
+        /// Like `update`, but validates the forest after every phase and stops at the
+        /// first broken invariant.
+        pub fn checked_update(&mut self) -> Result<(), InvariantError> {
+            self.months_elapsed += 1;
+
+            for phase in self.monthly_phases() {
+                self.run_phase(phase);
+                self.validate().map_err(|error| InvariantError {
+                    phase: Some(phase),
+                    ..error
+                })?;
+            }
+
+            Ok(())
+        }
+
+        fn monthly_phases(&self) -> impl Iterator<Item = Phase> {
+            let bear_lifecycle = self.rules.bear_lifecycle;
+            let yearly = self.months_elapsed.is_multiple_of(12);
+
+            Phase::ALL.into_iter().filter(move |phase| match phase {
+                Phase::BearLifecycle => bear_lifecycle,
+                Phase::Yearly => yearly,
+                _ => true,
+            })
+        }
+
+        /// Checks that every cell's fields are in range and that the position index, the
+        /// bears' lifecycle records and the yearly history all agree with the map.
+        pub fn validate(&self) -> Result<(), InvariantError> {
+            let error = |cell: Option<usize>, violation: Violation| InvariantError {
+                month: self.months_elapsed,
+                phase: None,
+                cell,
+                position: cell.map(|index| self.index_to_position(index)),
+                violation,
+            };
+
+            let sets = [
+                ("tree", &self.index.trees, TREE_MASK),
+                ("jack", &self.index.jacks, JACK_MASK),
+                ("bear", &self.index.bears, BEAR_MASK),
+                ("occupied", &self.index.occupied, ENTITY_MASK),
+            ];
+            let mut counts = [0; 4];
+
+            for (i, cell) in self.map.occupied() {
+                let bears = (cell & BEAR_MASK) >> BEAR_SHIFT;
+                let level = (cell & JACK_MASK) >> JACK_SHIFT;
+                let species = (cell & SPECIES_MASK) >> SPECIES_SHIFT;
+
+                if bears > 1 {
+                    return Err(error(Some(i), Violation::StackedBears { bears }));
+                }
+
+                if level > JACK_MAX_LEVEL {
+                    return Err(error(Some(i), Violation::JackLevel { level }));
+                }
+
+                if cell & TREE_MASK == 0 && species > 0 {
+                    return Err(error(Some(i), Violation::SpeciesWithoutTree { species }));
+                }
+
+                for (n, &(set, positions, mask)) in sets.iter().enumerate() {
+                    let present = cell & mask > 0;
+                    if positions.contains(i) != present {
+                        return Err(error(Some(i), Violation::IndexMismatch { set }));
+                    }
+                    counts[n] += present as usize;
+                }
+
+                if (bears > 0) != self.bears.contains_key(&i) {
+                    return Err(error(Some(i), Violation::UntrackedBear));
+                }
+            }
+
+            for (n, &(set, positions, mask)) in sets.iter().enumerate() {
+                if positions.len() != counts[n] {
+                    let stray = positions.iter().find(|&i| self.map[i] & mask == 0);
+                    return Err(error(stray, Violation::IndexMismatch { set }));
+                }
+            }
+
+            if self.bears.len() != counts[2] {
+                let stray = self.bears.keys().copied().find(|&i| self.map[i] & BEAR_MASK == 0);
+                return Err(error(stray, Violation::UntrackedBear));
+            }
+
+            if self.rules.bear_lifecycle {
+                for (&i, bear) in &self.bears {
+                    if bear.food == 0 || bear.food > BEAR_MAX_FOOD || bear.age >= BEAR_MAX_AGE {
+                        let violation = Violation::BearOutOfRange {
+                            age: bear.age,
+                            food: bear.food,
+                        };
+                        return Err(error(Some(i), violation));
+                    }
+                }
+            }
+
+            for years in self.history.windows(2) {
+                if years[1].year <= years[0].year {
+                    let violation = Violation::HistoryOutOfOrder {
+                        year: years[1].year,
+                    };
+                    return Err(error(None, violation));
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Runs a single phase of the monthly update without advancing the clock.
+        pub fn run_phase(&mut self, phase: Phase) {
+            match phase {
+                Phase::Trees => self.trigger_tree_event(),
+                Phase::Jacks => self.trigger_jack_event(),
+                Phase::Bears => self.trigger_bear_event(),
+                Phase::BearLifecycle => self.trigger_bear_lifecycle_event(),
+                Phase::Yearly => self.trigger_yearly_events(),
+            }
+        }
+    }
+
+    /// A run of chunks grown on its own thread. `window` reaches far enough past the strip
+    /// on either side to cover every cell a sapling can spread into.
+    #[cfg(feature = "parallel")]
+    struct TreeStrip<'a> {
+        window: MapWindow<'a>,
+        trees: &'a [usize],
+        rng: Random,
+    }
+
+    #[cfg(feature = "parallel")]
+    impl TreeStrip<'_> {
+        /// Returns `(index, old, new)` for every cell written.
+        fn grow(mut self, width: usize, height: usize) -> Vec<(usize, Cell, Cell)> {
+            let mut written = vec![];
+
+            for &i in self.trees {
+                let cell = self.window.get(i);
+                let aged = Forest::aged_tree(cell);
+                self.window.set(i, aged);
+                written.push((i, cell, aged));
+
+                let spawn_chance = Forest::get_sapling_spawn_chance(cell);
+                let result = self.rng.next() as u32 % 100;
+
+                if result <= spawn_chance {
+                    let mut position_candidates = Forest::adjacent_positions(i, width, height);
+                    position_candidates.retain(|position| {
+                        let cell = self.window.get(position);
+                        cell & TREE_MASK == 0
+                    });
+
+                    if let Some(choice) = self.rng.choose(&position_candidates) {
+                        let new = Forest::seeded_cell(self.window.get(choice), cell);
+                        let old = self.window.set(choice, new);
+                        written.push((choice, old, new));
+                    }
+                }
+            }
+
+            written
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    impl Forest {
+        /// Grows trees in strips of chunks, running every other strip concurrently so no two
+        /// threads ever touch the same chunk. Each strip draws from its own generator seeded
+        /// from the forest's, so results depend only on the seed and the thread count.
+        fn trigger_parallel_tree_event(&mut self, threads: usize) {
+            let width = self.width;
+            let height = self.height;
+            let total_chunks = self.map.chunk_count();
+            let reach = (width + 1).div_ceil(CHUNK_SIZE);
+            let strip_chunks = usize::max(total_chunks.div_ceil(threads.saturating_mul(2)), reach * 2);
+
+            let strips: Vec<(usize, usize)> = (0..total_chunks)
+                .step_by(strip_chunks)
+                .map(|start| (start, usize::min(start + strip_chunks, total_chunks)))
+                .collect();
+            let seeds: Vec<u64> = strips.iter().map(|_| self.rng.next() | 1).collect();
+            let positions = self.index.trees.to_vec();
+            let mut written = vec![];
+
+            for parity in [0, 1] {
+                let selected: Vec<usize> = (parity..strips.len()).step_by(2).collect();
+                let ranges: Vec<(usize, usize)> = selected
+                    .iter()
+                    .map(|&k| {
+                        let (start, end) = strips[k];
+                        (start.saturating_sub(reach), usize::min(end + reach, total_chunks))
+                    })
+                    .collect();
+
+                let jobs: Vec<TreeStrip> = self
+                    .map
+                    .windows_mut(&ranges)
+                    .into_iter()
+                    .zip(&selected)
+                    .map(|(window, &k)| {
+                        let (start, end) = strips[k];
+                        let first = positions.partition_point(|&i| i < start * CHUNK_SIZE);
+                        let last = positions.partition_point(|&i| i < end * CHUNK_SIZE);
+
+                        TreeStrip {
+                            window,
+                            trees: &positions[first..last],
+                            rng: Random::new(seeds[k]),
+                        }
+                    })
+                    .collect();
+
+                std::thread::scope(|scope| {
+                    let handles: Vec<_> = jobs
+                        .into_iter()
+                        .map(|job| scope.spawn(move || job.grow(width, height)))
+                        .collect();
+
+                    for handle in handles {
+                        written.extend(handle.join().expect("tree strip panicked"));
+                    }
+                });
+            }
+
+            for (index, old, new) in written {
+                self.index.update(index, old, new);
+
+                if old != new {
+                    self.dirty.insert(index);
+                }
             }
         }
     }
@@ -534,5 +1408,977 @@ pub mod forest {
     #[cfg(test)]
     mod tests {
         use super::*;
+        use crate::movement::{BearMovement, JackMovement};
+        use crate::policy::{PolicyKind, SustainableYieldPolicy, TreeDensityPolicy, YearlyPolicy};
+        use crate::consts::{
+            BEAR_CHASE_STEPS, BEAR_SIGHT_RADIUS, JACK_SIGHT_RADIUS, MAX_CHASE_STEPS,
+            MAX_POLICY_PERCENT, MAX_POLICY_QUOTA, MAX_SIGHT_RADIUS,
+        };
+        use crate::rules::Density;
+        use proptest::prelude::*;
+        use proptest::test_runner::TestCaseError;
+
+        fn empty_forest(width: usize, height: usize, rules: Rules) -> Forest {
+            let mut forest = Forest::with_rules(1, width, height, rules);
+            forest.map = ChunkedMap::new(width * height);
+            forest.index.rebuild(forest.map.iter());
+            forest.bears.clear();
+            forest
+        }
+
+        #[test]
+        fn adjacent_positions_are_clipped_at_corners_and_edges() {
+            let forest = empty_forest(4, 3, Rules::default());
+
+            assert_eq!(*forest.get_adjacent_positions(0), [1, 4, 5]);
+            assert_eq!(*forest.get_adjacent_positions(11), [6, 7, 10]);
+            assert_eq!(*forest.get_adjacent_positions(1), [0, 2, 4, 5, 6]);
+            assert_eq!(*forest.get_adjacent_positions(5), [0, 1, 2, 4, 6, 8, 9, 10]);
+        }
+
+        #[test]
+        fn adjacent_positions_on_single_row_and_column_maps() {
+            let row = empty_forest(3, 1, Rules::default());
+            assert_eq!(*row.get_adjacent_positions(0), [1]);
+            assert_eq!(*row.get_adjacent_positions(1), [0, 2]);
+
+            let column = empty_forest(1, 3, Rules::default());
+            assert_eq!(*column.get_adjacent_positions(1), [0, 2]);
+
+            let single = empty_forest(1, 1, Rules::default());
+            assert!(single.get_adjacent_positions(0).is_empty());
+        }
+
+        #[test]
+        fn tree_kind_thresholds() {
+            assert_eq!(Forest::get_tree_kind(0), TreeKind::None);
+            assert_eq!(Forest::get_tree_kind(1), TreeKind::Sapling);
+            assert_eq!(Forest::get_tree_kind(SAPLING_GROW_AGE - 1), TreeKind::Sapling);
+            assert_eq!(Forest::get_tree_kind(SAPLING_GROW_AGE), TreeKind::Mature);
+            assert_eq!(Forest::get_tree_kind(MATURE_GROW_AGE - 1), TreeKind::Mature);
+            assert_eq!(Forest::get_tree_kind(MATURE_GROW_AGE), TreeKind::Elder);
+            assert_eq!(Forest::get_tree_kind(TREE_MAX_AGE), TreeKind::Elder);
+
+            // Only the tree field decides the kind.
+            assert_eq!(Forest::get_tree_kind(0x1 << JACK_SHIFT), TreeKind::None);
+            assert_eq!(Forest::get_tree_kind(5 | (0x1 << BEAR_SHIFT)), TreeKind::Sapling);
+        }
+
+        #[test]
+        fn tree_tables_follow_kind() {
+            let cases = [
+                (0, 0, 0, NONE_HARVEST_AMOUNT),
+                (1, SAPLING_SPAWN_CHANCE, SAPLING_HARVEST_CHANCE, SAPLING_HARVEST_AMOUNT),
+                (SAPLING_GROW_AGE, MATURE_SPAWN_CHANCE, MATURE_HARVEST_CHANCE, MATURE_HARVEST_AMOUNT),
+                (MATURE_GROW_AGE, ELDER_SPAWN_CHANCE, ELDER_HARVEST_CHANCE, ELDER_HARVEST_AMOUNT),
+            ];
+
+            for (age, spawn, harvest, amount) in cases {
+                assert_eq!(Forest::get_sapling_spawn_chance(age), spawn);
+                assert_eq!(Forest::get_tree_harvest_chance(age), harvest);
+                assert_eq!(Forest::get_harvest_amount(age), amount);
+            }
+        }
+
+        #[test]
+        fn trees_stop_ageing_at_max_age() {
+            assert_eq!(Forest::aged_tree(7), 8);
+            assert_eq!(Forest::aged_tree(TREE_MAX_AGE), TREE_MAX_AGE);
+
+            let jack = 0x1 << JACK_SHIFT;
+            assert_eq!(Forest::aged_tree(TREE_MAX_AGE | jack), TREE_MAX_AGE | jack);
+        }
+
+        #[test]
+        fn de_age_tree_drops_to_the_start_of_its_kind() {
+            let mut forest = empty_forest(4, 1, Rules::default());
+            let jack = 0x2 << JACK_SHIFT;
+            forest.set_cell(0, 7 | jack);
+            forest.set_cell(1, 50 | jack);
+            forest.set_cell(2, 200 | jack);
+            forest.set_cell(3, jack);
+
+            for i in 0..4 {
+                forest.de_age_tree(i);
+            }
+
+            assert_eq!(forest.cell(0), 1 | jack);
+            assert_eq!(forest.cell(1), SAPLING_GROW_AGE | jack);
+            assert_eq!(forest.cell(2), MATURE_GROW_AGE | jack);
+            assert_eq!(forest.cell(3), jack);
+            assert_eq!(forest.tree_count(), 3);
+        }
+
+        #[test]
+        fn level_up_jack_is_capped_at_max_level() {
+            let mut forest = empty_forest(3, 1, Rules::default());
+            let tree = 30;
+            forest.set_cell(0, tree | (0x1 << JACK_SHIFT));
+            forest.set_cell(1, 0x4 << JACK_SHIFT);
+            forest.set_cell(2, 0x1 << JACK_SHIFT);
+
+            forest.level_up_jack(0, ELDER_HARVEST_AMOUNT * 10);
+            forest.level_up_jack(1, 1);
+            forest.level_up_jack(2, MATURE_HARVEST_AMOUNT);
+
+            assert_eq!(forest.cell(0), tree | (JACK_MAX_LEVEL << JACK_SHIFT));
+            assert_eq!(forest.cell(1), JACK_MAX_LEVEL << JACK_SHIFT);
+            assert_eq!(forest.cell(2), 0x3 << JACK_SHIFT);
+        }
+
+        #[test]
+        fn de_level_jack_never_drops_below_one() {
+            let mut forest = empty_forest(2, 1, Rules::default());
+            forest.set_cell(0, 0x3 << JACK_SHIFT);
+            forest.set_cell(1, 0x1 << JACK_SHIFT);
+
+            forest.de_level_jack(0);
+            forest.de_level_jack(1);
+
+            assert_eq!(forest.cell(0), 0x2 << JACK_SHIFT);
+            assert_eq!(forest.cell(1), 0x1 << JACK_SHIFT);
+        }
+
+        #[test]
+        fn jack_maul_chance_table() {
+            let expected = [(0, 90), (1, 81), (2, 74), (3, 69), (4, 60), (5, 50), (8, 25), (20, 25)];
+
+            for (level, chance) in expected {
+                assert_eq!(Forest::get_jack_maul_chance(level << JACK_SHIFT), chance, "level {}", level);
+            }
+        }
+
+        #[test]
+        fn inspect_describes_a_cell_and_its_neighbours() {
+            let rules = Rules {
+                bear_lifecycle: true,
+                ..Rules::default()
+            };
+            let mut forest = empty_forest(4, 3, rules);
+            forest.set_cell(5, (MATURE_GROW_AGE + 3) | (2 << SPECIES_SHIFT) | (3 << JACK_SHIFT));
+            forest.set_cell(0, 1);
+            forest.set_cell(6, 1 << JACK_SHIFT);
+            forest.spawn_bear(10, Bear::new());
+
+            let info = forest.inspect(1, 1).unwrap();
+            assert_eq!((info.index, info.position, info.flags), (5, (1, 1), 0));
+            assert_eq!(
+                info.tree,
+                Some(TreeInfo {
+                    kind: TreeKind::Elder,
+                    age: MATURE_GROW_AGE + 3,
+                    species: 2,
+                    spawn_chance: ELDER_SPAWN_CHANCE,
+                    harvest_chance: ELDER_HARVEST_CHANCE,
+                    harvest_amount: ELDER_HARVEST_AMOUNT,
+                })
+            );
+            assert_eq!(info.jack, Some(JackInfo { level: 3, maul_chance: 69 }));
+            assert_eq!(info.bear, None);
+            assert_eq!(
+                info.neighbours,
+                NeighbourCounts { cells: 8, trees: 1, jacks: 1, bears: 1 }
+            );
+
+            let edge = forest.inspect(2, 2).unwrap();
+            assert_eq!(edge.bear, Some(BearInfo { count: 1, lifecycle: Some(Bear::new()) }));
+            assert_eq!((edge.tree, edge.jack), (None, None));
+            assert_eq!(edge.neighbours.cells, 5);
+
+            assert_eq!(forest.inspect(4, 0), None);
+            assert_eq!(forest.inspect(0, 3), None);
+        }
+
+        #[test]
+        fn brushes_cover_the_expected_cells() {
+            let indices = |brush: Brush| brush.indices(5, 4);
+
+            assert_eq!(indices(Brush::Cell { x: 2, y: 1 }), Ok(vec![7]));
+            assert_eq!(
+                indices(Brush::Rect { x: 3, y: 2, width: 4, height: 4 }),
+                Ok(vec![13, 14, 18, 19])
+            );
+            assert_eq!(
+                indices(Brush::Circle { x: 0, y: 0, radius: 1 }),
+                Ok(vec![0, 1, 5])
+            );
+            assert_eq!(
+                indices(Brush::Circle { x: 2, y: 2, radius: 1 }),
+                Ok(vec![7, 11, 12, 13, 17])
+            );
+            assert_eq!(
+                indices(Brush::Rect { x: 5, y: 0, width: 1, height: 1 }),
+                Err(EditError::OutOfBounds { x: 5, y: 0 })
+            );
+        }
+
+        #[test]
+        fn huge_brushes_cover_the_whole_map_without_overflowing() {
+            let everything: Vec<usize> = (0..100).collect();
+
+            for radius in [14, 1 << 33, usize::MAX] {
+                assert_eq!(
+                    Brush::Circle { x: 0, y: 0, radius }.indices(10, 10),
+                    Ok(everything.clone()),
+                    "radius {}",
+                    radius
+                );
+            }
+            assert_eq!(
+                Brush::Circle { x: 0, y: 0, radius: 12 }.indices(10, 10).map(|cells| cells.len()),
+                Ok(97)
+            );
+            assert_eq!(
+                Brush::Rect { x: 0, y: 0, width: usize::MAX, height: usize::MAX }.indices(10, 10),
+                Ok(everything)
+            );
+        }
+
+        #[test]
+        fn paint_edits_cells_and_keeps_the_bookkeeping_valid() {
+            let rules = Rules {
+                bear_lifecycle: true,
+                ..Rules::default()
+            };
+            let mut forest = empty_forest(6, 5, rules);
+
+            let rect = Brush::Rect { x: 1, y: 1, width: 3, height: 2 };
+            assert_eq!(forest.paint(rect, Edit::SetTree { age: 40, species: 3 }), Ok(6));
+            assert_eq!(forest.paint(rect, Edit::PlaceJack { level: 2 }), Ok(6));
+            assert_eq!(forest.paint(rect, Edit::PlaceBear), Ok(6));
+            assert_eq!(forest.paint(rect, Edit::PlaceBear), Ok(0));
+            assert_eq!((forest.tree_count(), forest.jack_count(), forest.bear_count()), (6, 6, 6));
+            assert_eq!(forest.tree_kind_at(7), TreeKind::Mature);
+            assert_eq!(forest.species_at(7), 3);
+            assert_eq!(forest.bear_at(7), Some(&Bear::new()));
+            assert_eq!(forest.validate(), Ok(()));
+
+            forest.set_tree(1, 1, 1).unwrap();
+            assert_eq!((forest.tree_kind_at(7), forest.species_at(7)), (TreeKind::Sapling, 3));
+
+            forest.remove_bear(1, 1).unwrap();
+            forest.remove_jack(2, 1).unwrap();
+            forest.clear_tree(3, 1).unwrap();
+            assert_eq!(forest.bear_at(7), None);
+            assert_eq!((forest.tree_count(), forest.jack_count(), forest.bear_count()), (5, 5, 5));
+            assert_eq!(forest.species_at(9), 0);
+            assert_eq!(forest.validate(), Ok(()));
+
+            let before = forest.cells().collect::<Vec<_>>();
+            let invalid = [
+                (Edit::SetTree { age: 0, species: 0 }, EditError::TreeAge { age: 0 }),
+                (
+                    Edit::SetTree { age: TREE_MAX_AGE + 1, species: 0 },
+                    EditError::TreeAge { age: TREE_MAX_AGE + 1 },
+                ),
+                (Edit::SetTree { age: 1, species: 16 }, EditError::Species { species: 16 }),
+                (
+                    Edit::PlaceJack { level: JACK_MAX_LEVEL + 1 },
+                    EditError::JackLevel { level: JACK_MAX_LEVEL + 1 },
+                ),
+            ];
+            for (edit, error) in invalid {
+                assert_eq!(forest.paint(rect, edit), Err(error));
+            }
+            assert_eq!(forest.place_bear(6, 0), Err(EditError::OutOfBounds { x: 6, y: 0 }));
+            assert!(forest.cells().eq(before));
+        }
+
+        #[test]
+        fn validate_reports_stacked_bears_with_their_position() {
+            let mut forest = empty_forest(4, 3, Rules::default());
+            assert_eq!(forest.validate(), Ok(()));
+
+            forest.spawn_bear(6, Bear::new());
+            forest.place_entity(6, BEAR_SHIFT);
+
+            let error = forest.validate().unwrap_err();
+            assert_eq!(error.violation, Violation::StackedBears { bears: 2 });
+            assert_eq!((error.cell, error.position, error.phase), (Some(6), Some((2, 1)), None));
+        }
+
+        #[test]
+        fn validate_catches_index_drift_and_untracked_bears() {
+            let mut forest = empty_forest(4, 3, Rules::default());
+            forest.index.update(5, 0, 1);
+            let error = forest.validate().unwrap_err();
+            assert_eq!(error.violation, Violation::IndexMismatch { set: "tree" });
+            assert_eq!(error.cell, Some(5));
+
+            let mut forest = empty_forest(4, 3, Rules::default());
+            forest.place_entity(2, BEAR_SHIFT);
+            let error = forest.validate().unwrap_err();
+            assert_eq!((error.cell, error.violation), (Some(2), Violation::UntrackedBear));
+        }
+
+        #[test]
+        fn checked_update_names_the_phase_that_broke_an_invariant() {
+            let mut forest = empty_forest(4, 3, Rules::default());
+            forest.set_cell(0, (JACK_MAX_LEVEL + 1) << JACK_SHIFT);
+
+            let error = forest.checked_update().unwrap_err();
+            assert_eq!(error.phase, Some(Phase::Trees));
+            assert_eq!(error.month, 1);
+            assert_eq!(error.violation, Violation::JackLevel { level: JACK_MAX_LEVEL + 1 });
+            assert_eq!(
+                error.to_string(),
+                "month 1, after trees phase, cell 0 (0, 0): jack level 6 is above the maximum"
+            );
+        }
+
+        #[test]
+        fn try_new_rejects_unusable_arguments() {
+            assert_eq!(
+                Forest::try_new(1, 0, 8).err(),
+                Some(ForestError::ZeroDimensions { width: 0, height: 8 })
+            );
+            assert_eq!(
+                Forest::try_new(1, usize::MAX, 2).err(),
+                Some(ForestError::TooLarge { width: usize::MAX, height: 2 })
+            );
+            assert_eq!(
+                Forest::try_new(1, 1 << 40, 1 << 20).err(),
+                Some(ForestError::TooLarge { width: 1 << 40, height: 1 << 20 })
+            );
+            assert_eq!(
+                Forest::try_new(1, MAX_CELLS + 1, 1).err(),
+                Some(ForestError::TooLarge { width: MAX_CELLS + 1, height: 1 })
+            );
+            assert_eq!(
+                Forest::try_new(1, 1 << 15, (1 << 15) + 1).err(),
+                Some(ForestError::TooLarge { width: 1 << 15, height: (1 << 15) + 1 })
+            );
+
+            let empty = Density { trees: 0.0, jacks: 0.0, bears: 0.0 };
+            let rules = Rules { density: empty, ..Rules::default() };
+            assert!(Forest::try_with_rules(1, 1 << 15, 1 << 15, rules).is_ok());
+            assert_eq!(Forest::try_new(0, 12, 8).err(), Some(ForestError::DegenerateSeed));
+
+            for density in [
+                Density { trees: 1.5, ..Density::default() },
+                Density { jacks: -0.1, ..Density::default() },
+                Density { bears: f32::NAN, ..Density::default() },
+            ] {
+                let rules = Rules { density, ..Rules::default() };
+                assert!(matches!(
+                    Forest::try_with_rules(1, 12, 8, rules),
+                    Err(ForestError::InvalidDensity { .. })
+                ));
+            }
+
+            let full = Density { trees: 1.0, jacks: 1.0, bears: 1.0 };
+            let rules = Rules { density: full, ..Rules::default() };
+            let forest = Forest::try_with_rules(1, 3, 3, rules).unwrap();
+            assert_eq!((forest.tree_count(), forest.jack_count(), forest.bear_count()), (9, 9, 9));
+        }
+
+        #[test]
+        fn set_rules_changes_later_updates() {
+            let mut forest = Forest::new(123123, 20, 20);
+            let mut same = forest.clone();
+            let rules = Rules {
+                bear_lifecycle: true,
+                economy: true,
+                policy: PolicyKind::Profit,
+                ..Rules::default()
+            };
+
+            let density = Density { trees: 2.0, ..Density::default() };
+            assert_eq!(
+                forest.set_rules(Rules { density, ..rules.clone() }),
+                Err(ForestError::InvalidDensity { density })
+            );
+            assert_eq!(forest.rules, Rules::default());
+
+            forest.set_rules(rules.clone()).unwrap();
+            let mut fresh = Forest::with_rules(123123, 20, 20, rules);
+            for _ in 0..24 {
+                forest.update();
+                same.update();
+                fresh.update();
+            }
+
+            assert_eq!(forest.state_hash(), fresh.state_hash());
+            assert_ne!(forest.state_hash(), same.state_hash());
+        }
+
+        #[test]
+        fn classic_year_hires_for_excess_lumber_and_culls_a_bear() {
+            let mut forest = empty_forest(10, 10, Rules::default());
+            for i in 0..3 {
+                forest.place_entity(i, JACK_SHIFT);
+            }
+            forest.spawn_bear(50, Bear::new());
+            forest.spawn_bear(60, Bear::new());
+            forest.months_elapsed = 12;
+            forest.yearly_lumber = 35;
+            forest.yearly_mauls = 1;
+
+            forest.run_phase(Phase::Yearly);
+
+            // (35 lumber - 3 jacks) / 10 = 3 new jacks, and a maul means one bear goes.
+            assert_eq!(forest.jack_count(), 6);
+            assert_eq!(forest.bear_count(), 1);
+
+            let stats = forest.history.last().unwrap();
+            assert_eq!((stats.year, stats.lumber, stats.mauls), (1, 35, 1));
+            assert_eq!((stats.jacks_hired, stats.jacks_fired), (3, 0));
+            assert_eq!((stats.jacks, stats.bears), (6, 1));
+            assert_eq!((forest.yearly_lumber, forest.yearly_mauls), (0, 0));
+        }
+
+        #[test]
+        fn classic_year_culls_a_jack_and_adds_a_bear_when_nothing_happened() {
+            let mut forest = empty_forest(10, 10, Rules::default());
+            for i in 0..3 {
+                forest.place_entity(i, JACK_SHIFT);
+            }
+            forest.yearly_lumber = 2;
+
+            forest.run_phase(Phase::Yearly);
+
+            assert_eq!(forest.jack_count(), 2);
+            assert_eq!(forest.bear_count(), 1);
+            assert_eq!(forest.history.last().unwrap().jacks_fired, 1);
+        }
+
+        #[test]
+        fn hiring_stops_when_the_map_is_full() {
+            let mut forest = empty_forest(2, 2, Rules::default());
+            forest.place_entity(0, JACK_SHIFT);
+            forest.yearly_lumber = 500;
+
+            forest.run_phase(Phase::Yearly);
+
+            // The bear added for a maul-free year takes the last free cell.
+            assert_eq!(forest.jack_count() + forest.bear_count(), 4);
+            assert_eq!(forest.index.empty_len(), 0);
+        }
+
+        #[test]
+        fn trees_age_past_the_legacy_limit_and_keep_their_species() {
+            let mut forest = empty_forest(3, 3, Rules::default());
+            forest.set_cell(4, 300 | (3 << SPECIES_SHIFT));
+
+            forest.age_tree(4, forest.cell(4));
+            assert_eq!(forest.cell(4) & TREE_MASK, 301);
+            assert_eq!(forest.species_at(4), 3);
+            assert_eq!(forest.tree_kind_at(4), TreeKind::Elder);
+
+            assert_eq!(Forest::seeded_cell(0, forest.cell(4)), 1 | (3 << SPECIES_SHIFT));
+
+            forest.remove_entity(4, TREE_REMOVE_MASK);
+            assert_eq!(forest.cell(4), 0);
+        }
+
+        #[test]
+        fn legacy_export_saturates_wide_fields() {
+            let cell = 300 | (2 << SPECIES_SHIFT) | (20 << JACK_SHIFT) | (1 << BEAR_SHIFT);
+            assert_eq!(cell::to_legacy(cell), 0x1FFF);
+
+            for legacy in [0x0000, 0x0001, 0x00FF, 0x0300, 0x1000, 0x1578] {
+                assert_eq!(cell::to_legacy(cell::from_legacy(legacy)), legacy);
+            }
+        }
+
+        #[test]
+        fn hungry_bear_starves() {
+            let rules = Rules {
+                bear_lifecycle: true,
+                ..Rules::default()
+            };
+            let mut forest = empty_forest(3, 3, rules);
+            forest.spawn_bear(4, Bear { age: BEAR_MATURE_AGE, food: 1 });
+
+            forest.trigger_bear_lifecycle_event();
+
+            assert_eq!(forest.map[4] & BEAR_MASK, 0);
+            assert!(forest.bear_at(4).is_none());
+            assert_eq!(forest.yearly_bear_deaths, 1);
+        }
+
+        #[test]
+        fn well_fed_bear_breeds_into_adjacent_cell() {
+            let rules = Rules {
+                bear_lifecycle: true,
+                ..Rules::default()
+            };
+            let mut forest = empty_forest(3, 3, rules);
+            forest.spawn_bear(4, Bear { age: BEAR_MATURE_AGE, food: BEAR_MAX_FOOD });
+
+            forest.trigger_bear_lifecycle_event();
+
+            assert_eq!(forest.bear_count(), 2);
+            assert_eq!(forest.yearly_bear_births, 1);
+            assert_eq!(
+                forest.bear_at(4).map(|bear| bear.food),
+                Some(BEAR_MAX_FOOD - BEAR_MONTHLY_HUNGER - BEAR_REPRODUCE_COST)
+            );
+        }
+
+        #[test]
+        fn spatial_index_matches_full_map_scan() {
+            let mut forest = Forest::new(123123, 40, 30);
+
+            for _ in 0..600 {
+                forest.update();
+
+                let scan = |mask: Cell| -> Vec<usize> {
+                    (0..forest.map.len()).filter(|&i| forest.map[i] & mask > 0).collect()
+                };
+                assert_eq!(forest.index.trees.to_vec(), scan(TREE_MASK));
+                assert_eq!(forest.index.jacks.to_vec(), scan(JACK_MASK));
+                assert_eq!(forest.index.bears.to_vec(), scan(BEAR_MASK));
+
+                let empty: Vec<usize> = (0..forest.map.len()).filter(|&i| forest.map[i] == 0).collect();
+                for (n, &i) in empty.iter().enumerate() {
+                    assert_eq!(forest.index.nth_empty(n), Some(i));
+                }
+                assert_eq!(forest.index.nth_empty(empty.len()), None);
+            }
+        }
+
+        #[cfg(feature = "parallel")]
+        #[test]
+        fn parallel_tree_phase_is_deterministic_per_thread_count() {
+            let run = |threads: usize| {
+                let rules = Rules {
+                    tree_threads: threads,
+                    ..Rules::default()
+                };
+                let mut forest = Forest::with_rules(123123, 120, 80, rules);
+                for _ in 0..240 {
+                    forest.update();
+                }
+                forest
+            };
+
+            for threads in [2, 3, 4] {
+                let a = run(threads);
+                let b = run(threads);
+                assert_eq!(a.map, b.map);
+
+                let mut rebuilt = a.index.clone();
+                rebuilt.rebuild(a.cells());
+                assert_eq!(a.index, rebuilt);
+            }
+        }
+
+        #[test]
+        fn applying_changes_keeps_a_copy_in_sync() {
+            let rule_sets = [
+                Rules {
+                    bear_lifecycle: true,
+                    ..Rules::default()
+                },
+                #[cfg(feature = "parallel")]
+                Rules {
+                    tree_threads: 4,
+                    ..Rules::default()
+                },
+            ];
+
+            for rules in rule_sets {
+                let mut forest = Forest::with_rules(42, 120, 80, rules);
+                let mut copy = vec![0; forest.map.len()];
+
+                for month in 0..120 {
+                    let dirty: Vec<usize> = forest.dirty_cells().collect();
+                    let changes = forest.take_changes();
+                    assert_eq!(dirty, changes.iter().map(|&(i, _)| i).collect::<Vec<_>>());
+
+                    for (i, cell) in changes {
+                        copy[i] = cell;
+                    }
+                    assert!(forest.cells().eq(copy.iter().copied()), "month {}", month);
+                    assert_eq!(forest.dirty_cells().count(), 0);
+
+                    forest.update();
+                }
+            }
+        }
+
+        #[test]
+        fn sparse_forest_only_allocates_occupied_chunks() {
+            let rules = Rules {
+                density: Density {
+                    trees: 0.00001,
+                    jacks: 0.000001,
+                    bears: 0.0,
+                },
+                ..Rules::default()
+            };
+            let mut forest = Forest::with_rules(123123, 20_000, 20_000, rules);
+            let total_chunks = forest.map.chunk_count();
+            assert!(forest.active_chunks() <= forest.tree_count() + forest.jack_count());
+
+            for _ in 0..24 {
+                forest.update();
+            }
+            assert!(forest.active_chunks() < total_chunks / 10);
+            // Far below the 1.6GB a dense copy of the cells would take.
+            assert!(forest.heap_size() < 200 << 20);
+
+            let cleared = forest.index.occupied.to_vec();
+            for &i in &cleared {
+                forest.set_cell(i, 0);
+            }
+            assert_eq!(forest.active_chunks(), 0);
+            assert_eq!(forest.index.empty_len(), 20_000 * 20_000);
+            assert!(forest.heap_size() < 20 << 20);
+        }
+
+        fn state_hash_after(seed: u64, width: usize, height: usize, months: u32, rules: Rules) -> u64 {
+            let mut forest = Forest::with_rules(seed, width, height, rules);
+            while forest.months_elapsed < months {
+                forest.update();
+            }
+            forest.state_hash()
+        }
+
+        // These pin the simulation's behaviour. If one fails, a change has altered how the
+        // forest evolves; only update the hash when that change was intended.
+        #[test]
+        fn golden_state_hash_for_a_long_run() {
+            assert_eq!(
+                state_hash_after(123123, 120, 80, 4800, Rules::default()),
+                0x537582ff38ec80fc
+            );
+        }
+
+        #[test]
+        fn golden_state_hashes() {
+            assert_eq!(
+                state_hash_after(123123, 40, 30, 1200, Rules::default()),
+                0x294b0bfcdb57fa99
+            );
+
+            let bear_lifecycle = Rules {
+                bear_lifecycle: true,
+                ..Rules::default()
+            };
+            assert_eq!(state_hash_after(42, 40, 30, 1200, bear_lifecycle), 0x5ef46049d19ee951);
+
+            let economy = Rules {
+                economy: true,
+                policy: PolicyKind::Profit,
+                ..Rules::default()
+            };
+            assert_eq!(state_hash_after(7, 40, 30, 1200, economy), 0x08c772063149100d);
+
+            let movement = Rules {
+                jack_movement: JackMovement::Greedy {
+                    sight: JACK_SIGHT_RADIUS,
+                },
+                bear_movement: BearMovement::Hunt {
+                    sight: BEAR_SIGHT_RADIUS,
+                    chase: BEAR_CHASE_STEPS,
+                },
+                ..Rules::default()
+            };
+            assert_eq!(state_hash_after(99, 40, 30, 1200, movement), 0x8cfab44a6b3cde13);
+        }
+
+        #[test]
+        fn state_hash_tracks_more_than_the_map() {
+            let forest = Forest::new(123123, 40, 30);
+            let mut advanced = forest.clone();
+            advanced.rng.next();
+
+            assert_eq!(forest.map_hash(), advanced.map_hash());
+            assert_ne!(forest.state_hash(), advanced.state_hash());
+            assert_eq!(forest.state_hash(), forest.clone().state_hash());
+        }
+
+        #[test]
+        fn economy_sells_stockpile_and_pays_wages() {
+            let mut economy = Economy::new();
+            economy.store(21);
+
+            let accounts = economy.close_year(Economy::jack_wage(1) * 3);
+
+            assert_eq!(accounts.lumber_sold, 11);
+            assert_eq!(accounts.revenue, 110);
+            assert_eq!(accounts.wages, 45);
+            assert_eq!(accounts.profit, 65);
+            assert_eq!(economy.stockpile, 10);
+            assert_eq!(economy.funds, 65);
+            assert_eq!(economy.affordable_hires(accounts.profit), 1);
+        }
+
+        #[test]
+        fn economy_handles_stockpiles_past_u32() {
+            let mut economy = Economy::new();
+            economy.store(u32::MAX);
+            economy.store(u32::MAX);
+            assert_eq!(economy.stockpile, 2 * u32::MAX as u64);
+
+            let accounts = economy.close_year(u32::MAX as u64 * 2);
+
+            assert_eq!(accounts.lumber_sold, u32::MAX as u64);
+            assert_eq!(accounts.revenue, u32::MAX as i64 * 10);
+            assert_eq!(accounts.profit, u32::MAX as i64 * 8);
+            assert_eq!(economy.stockpile, u32::MAX as u64);
+        }
+
+        #[test]
+        fn policy_kinds_round_trip_through_strings() {
+            for spec in ["classic", "profit", "fixed-quota:40:5", "tree-density:12", "sustainable-yield:8", "none"] {
+                let kind: PolicyKind = spec.parse().unwrap();
+                assert_eq!(kind.to_string(), spec);
+            }
+
+            assert!("fixed-quota:1:2:3".parse::<PolicyKind>().is_err());
+            assert!("clear-cut".parse::<PolicyKind>().is_err());
+        }
+
+        #[test]
+        fn policies_reject_huge_parameters() {
+            let quota = MAX_POLICY_QUOTA;
+            let percent = MAX_POLICY_PERCENT;
+            assert!(format!("fixed-quota:{}:{}", quota, quota).parse::<PolicyKind>().is_ok());
+            assert!(format!("tree-density:{}", percent).parse::<PolicyKind>().is_ok());
+
+            for spec in [
+                format!("fixed-quota:{}:0", quota + 1),
+                format!("fixed-quota:0:{}", usize::MAX),
+                format!("tree-density:{}", percent + 1),
+                format!("sustainable-yield:{}", usize::MAX),
+            ] {
+                assert!(spec.parse::<PolicyKind>().is_err(), "{}", spec);
+            }
+        }
+
+        #[test]
+        fn hiring_stops_once_the_map_is_full() {
+            let rules = Rules {
+                policy: PolicyKind::FixedQuota { jacks: MAX_POLICY_QUOTA, bears: MAX_POLICY_QUOTA },
+                ..Rules::default()
+            };
+            let mut forest = empty_forest(6, 5, rules);
+            while forest.months_elapsed < 12 {
+                forest.update();
+            }
+
+            assert_eq!(forest.index.empty_len(), 0);
+            assert!(forest.validate().is_ok());
+        }
+
+        #[test]
+        fn ratio_policies_saturate_on_huge_forests() {
+            let context = PolicyContext { trees: usize::MAX, jacks: 1, ..PolicyContext::default() };
+            let density = TreeDensityPolicy { jacks_per_hundred_trees: MAX_POLICY_PERCENT };
+            let yield_policy = SustainableYieldPolicy { yield_percent: MAX_POLICY_PERCENT };
+
+            assert_eq!(density.adjust_jacks(&context), Adjustment::Hire(usize::MAX / 100 - 1));
+            assert!(matches!(yield_policy.adjust_jacks(&context), Adjustment::Hire(_)));
+        }
+
+        #[test]
+        fn greedy_jack_steps_towards_mature_tree() {
+            let mut forest = empty_forest(5, 5, Rules::default());
+            forest.set_cell(4, MATURE_GROW_AGE);
+
+            let strategy = JackMovement::Greedy { sight: 3 }.build();
+            let mut candidates = forest.get_adjacent_positions(12);
+            strategy.filter_steps(&forest, 12, &mut candidates);
+
+            assert_eq!(*candidates, [8]);
+        }
+
+        #[test]
+        fn huge_sight_scans_the_whole_map_without_overflowing() {
+            let mut forest = empty_forest(5, 5, Rules::default());
+            forest.set_cell(24, MATURE_GROW_AGE);
+
+            let strategy = JackMovement::Greedy { sight: usize::MAX }.build();
+            let mut candidates = forest.get_adjacent_positions(12);
+            strategy.filter_steps(&forest, 12, &mut candidates);
+            assert_eq!(*candidates, [18]);
+
+            assert_eq!(
+                format!("greedy:{}", MAX_SIGHT_RADIUS).parse::<JackMovement>(),
+                Ok(JackMovement::Greedy { sight: MAX_SIGHT_RADIUS })
+            );
+            assert!(format!("greedy:{}", MAX_SIGHT_RADIUS + 1).parse::<JackMovement>().is_err());
+            assert!("greedy:18446744073709551615".parse::<JackMovement>().is_err());
+        }
+
+        #[test]
+        fn sapling_avoiding_jack_stays_put_among_saplings() {
+            let mut forest = empty_forest(3, 3, Rules::default());
+            for i in 0..9 {
+                if i != 4 {
+                    forest.set_cell(i, 1);
+                }
+            }
+
+            let strategy = JackMovement::AvoidSaplings.build();
+            let mut candidates = forest.get_adjacent_positions(4);
+            strategy.filter_steps(&forest, 4, &mut candidates);
+
+            assert!(candidates.is_empty());
+        }
+
+        #[test]
+        fn hunting_bear_closes_in_on_jack_within_sight() {
+            let mut forest = empty_forest(7, 1, Rules::default());
+            forest.set_cell(6, 0x1 << JACK_SHIFT);
+
+            let strategy = BearMovement::Hunt { sight: 4, chase: 6 }.build();
+            assert_eq!(strategy.steps_per_month(&forest, 2, BEAR_WANDERS_PER_MONTH), 6);
+            assert_eq!(strategy.steps_per_month(&forest, 0, BEAR_WANDERS_PER_MONTH), BEAR_WANDERS_PER_MONTH);
+
+            let mut candidates = forest.get_adjacent_positions(2);
+            strategy.filter_steps(&forest, 2, &mut candidates);
+            assert_eq!(*candidates, [3]);
+        }
+
+        #[test]
+        fn hunt_rejects_absurd_sight_and_chase() {
+            let limits = format!("hunt:{}:{}", MAX_SIGHT_RADIUS, MAX_CHASE_STEPS);
+            assert_eq!(
+                limits.parse::<BearMovement>(),
+                Ok(BearMovement::Hunt { sight: MAX_SIGHT_RADIUS, chase: MAX_CHASE_STEPS })
+            );
+
+            for spec in [
+                format!("hunt:{}", MAX_SIGHT_RADIUS + 1),
+                format!("hunt:4:{}", MAX_CHASE_STEPS + 1),
+                "hunt:18446744073709551615".to_string(),
+                "hunt:4:4294967295".to_string(),
+            ] {
+                assert!(spec.parse::<BearMovement>().is_err(), "{}", spec);
+            }
+
+            let mut forest = empty_forest(7, 1, Rules::default());
+            forest.set_cell(6, 0x1 << JACK_SHIFT);
+            let strategy = BearMovement::Hunt { sight: usize::MAX, chase: 6 }.build();
+            assert_eq!(strategy.steps_per_month(&forest, 0, BEAR_WANDERS_PER_MONTH), 6);
+        }
+
+        #[test]
+        fn fixed_quota_policy_moves_towards_its_targets() {
+            let policy = PolicyKind::FixedQuota { jacks: 10, bears: 2 }.build();
+            let context = PolicyContext {
+                jacks: 4,
+                bears: 5,
+                ..PolicyContext::default()
+            };
+
+            assert_eq!(policy.adjust_jacks(&context), Adjustment::Hire(6));
+            assert_eq!(policy.adjust_bears(&context), Adjustment::Cull(3));
+        }
+
+        fn check_invariants(forest: &Forest) -> Result<(), TestCaseError> {
+            let cells = forest.width * forest.height;
+            let (mut trees, mut jacks, mut bears) = (0, 0, 0);
+
+            for (i, cell) in forest.cells().enumerate() {
+                let bear = (cell & BEAR_MASK) >> BEAR_SHIFT;
+                let level = (cell & JACK_MASK) >> JACK_SHIFT;
+                prop_assert!(bear <= 1, "cell {} holds {} bears", i, bear);
+                prop_assert!(level <= JACK_MAX_LEVEL, "cell {} has jack level {}", i, level);
+                prop_assert!(cell & TREE_MASK > 0 || cell & SPECIES_MASK == 0, "cell {} has a species but no tree", i);
+
+                trees += (cell & TREE_MASK > 0) as usize;
+                jacks += (level > 0) as usize;
+                bears += bear as usize;
+            }
+
+            prop_assert_eq!(forest.tree_count(), trees);
+            prop_assert_eq!(forest.jack_count(), jacks);
+            prop_assert_eq!(forest.bear_count(), bears);
+            prop_assert!(trees <= cells && jacks <= cells && bears <= cells);
+            prop_assert!(forest.index.empty_len() <= cells);
+            prop_assert_eq!(forest.validate(), Ok(()));
+
+            let tracked: Vec<usize> = forest.bears.keys().copied().collect();
+            prop_assert_eq!(tracked, forest.index.bears.to_vec());
+
+            if forest.rules.bear_lifecycle {
+                for bear in forest.bears.values() {
+                    prop_assert!(bear.food > 0 && bear.food <= BEAR_MAX_FOOD);
+                    prop_assert!(bear.age < BEAR_MAX_AGE);
+                }
+            }
+
+            if let Some(stats) = forest.history.last() {
+                prop_assert_eq!(stats.year * 12, forest.months_elapsed - forest.months_elapsed % 12);
+                prop_assert!((stats.trees as usize) <= cells);
+                prop_assert!((stats.jacks as usize) <= cells);
+                prop_assert!((stats.bears as usize) <= cells);
+            }
+
+            Ok(())
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(48))]
+
+            // A seed of zero never leaves the generator's fixed point, so it is excluded.
+            #[test]
+            fn invariants_hold_over_random_runs(
+                seed in 1..u64::MAX,
+                width in 1usize..24,
+                height in 1usize..24,
+                bear_lifecycle in any::<bool>(),
+                economy in any::<bool>(),
+                greedy_jacks in any::<bool>(),
+                hunting_bears in any::<bool>(),
+            ) {
+                let rules = Rules {
+                    bear_lifecycle,
+                    economy,
+                    jack_movement: if greedy_jacks {
+                        JackMovement::Greedy { sight: JACK_SIGHT_RADIUS }
+                    } else {
+                        JackMovement::RandomWalk
+                    },
+                    bear_movement: if hunting_bears {
+                        BearMovement::Hunt { sight: BEAR_SIGHT_RADIUS, chase: BEAR_CHASE_STEPS }
+                    } else {
+                        BearMovement::RandomWalk
+                    },
+                    ..Rules::default()
+                };
+
+                let mut forest = Forest::with_rules(seed, width, height, rules);
+                check_invariants(&forest)?;
+
+                for _ in 0..120 {
+                    forest.update();
+                    check_invariants(&forest)?;
+                }
+            }
+
+            #[test]
+            fn legacy_export_round_trips_legacy_cells(legacy in any::<u16>()) {
+                prop_assert_eq!(cell::to_legacy(cell::from_legacy(legacy)), legacy);
+            }
+
+            #[test]
+            fn position_sets_agree_with_a_plain_set(
+                occupied in proptest::collection::btree_set(0usize..10_000, 0..200),
+            ) {
+                let mut index = EntityIndex::new(10_000);
+                for &i in &occupied {
+                    index.update(i, 0, 0x1 << JACK_SHIFT);
+                }
+
+                let empty: Vec<usize> = (0..10_000).filter(|i| !occupied.contains(i)).collect();
+                prop_assert_eq!(index.empty_len(), empty.len());
+                for n in (0..empty.len()).step_by(97) {
+                    prop_assert_eq!(index.nth_empty(n), Some(empty[n]));
+                }
+                prop_assert_eq!(index.nth_empty(empty.len()), None);
+
+                let members: Vec<usize> = occupied.iter().copied().collect();
+                prop_assert_eq!(index.jacks.to_vec(), members.clone());
+                for (n, &i) in members.iter().enumerate() {
+                    prop_assert_eq!(index.jacks.nth(n), Some(i));
+                }
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,342 @@
+use std::{error::Error, fmt, str::FromStr, sync::Arc};
+
+use crate::consts::{
+    BEAR_CHASE_STEPS, BEAR_MASK, BEAR_SIGHT_RADIUS, JACK_MASK, JACK_SIGHT_RADIUS, MAX_CHASE_STEPS,
+    MAX_SIGHT_RADIUS,
+};
+use crate::forest::{Forest, Neighbours, TreeKind};
+
+pub trait MovementStrategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Narrows the free cells an entity may step into from `from`. The forest picks
+    /// randomly among whatever is left, and an empty list means the entity stays put.
+    fn filter_steps(&self, forest: &Forest, from: usize, candidates: &mut Neighbours);
+
+    /// How many steps the entity at `from` may take this month.
+    fn steps_per_month(&self, _forest: &Forest, _from: usize, default: u32) -> u32 {
+        default
+    }
+}
+
+fn distance(forest: &Forest, a: usize, b: usize) -> usize {
+    let (ax, ay) = forest.index_to_position(a);
+    let (bx, by) = forest.index_to_position(b);
+    usize::max(ax.abs_diff(bx), ay.abs_diff(by))
+}
+
+/// Finds the closest cell within `radius` of `from` matching `is_target`, scanning row by row
+/// so that ties always resolve the same way.
+pub fn find_nearest<F>(forest: &Forest, from: usize, radius: usize, is_target: F) -> Option<usize>
+where
+    F: Fn(usize) -> bool,
+{
+    let (x, y) = forest.index_to_position(from);
+    let mut nearest: Option<(usize, usize)> = None;
+
+    for ny in y.saturating_sub(radius)..=usize::min(y.saturating_add(radius), forest.height - 1) {
+        for nx in x.saturating_sub(radius)..=usize::min(x.saturating_add(radius), forest.width - 1) {
+            let index = forest.position_to_index(nx, ny);
+            if index == from || !is_target(index) {
+                continue;
+            }
+
+            let d = distance(forest, from, index);
+            if nearest.is_none_or(|(_, best)| d < best) {
+                nearest = Some((index, d));
+            }
+        }
+    }
+
+    nearest.map(|(index, _)| index)
+}
+
+/// Keeps only the candidates that get closest to `target`.
+pub fn step_towards(forest: &Forest, target: usize, candidates: &mut Neighbours) {
+    if let Some(best) = candidates
+        .iter()
+        .map(|&c| distance(forest, c, target))
+        .min()
+    {
+        candidates.retain(|c| distance(forest, c, target) == best);
+    }
+}
+
+pub struct RandomWalk;
+
+impl MovementStrategy for RandomWalk {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn filter_steps(&self, _forest: &Forest, _from: usize, _candidates: &mut Neighbours) {}
+}
+
+pub struct GreedyHarvest {
+    pub sight: usize,
+}
+
+impl MovementStrategy for GreedyHarvest {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn filter_steps(&self, forest: &Forest, from: usize, candidates: &mut Neighbours) {
+        let target = find_nearest(forest, from, self.sight, |index| {
+            matches!(
+                forest.tree_kind_at(index),
+                TreeKind::Mature | TreeKind::Elder
+            )
+        });
+
+        if let Some(target) = target {
+            step_towards(forest, target, candidates);
+
+            if candidates
+                .iter()
+                .any(|&c| forest.tree_kind_at(c) != TreeKind::Sapling)
+            {
+                candidates.retain(|c| forest.tree_kind_at(c) != TreeKind::Sapling);
+            }
+        }
+    }
+}
+
+pub struct AvoidBears;
+
+impl MovementStrategy for AvoidBears {
+    fn name(&self) -> &'static str {
+        "avoid-bears"
+    }
+
+    fn filter_steps(&self, forest: &Forest, _from: usize, candidates: &mut Neighbours) {
+        candidates.retain(|c| {
+            (forest.cell(c) & BEAR_MASK) == 0
+                && forest
+                    .get_adjacent_positions(c)
+                    .into_iter()
+                    .all(|n| (forest.cell(n) & BEAR_MASK) == 0)
+        });
+    }
+}
+
+pub struct AvoidSaplings;
+
+impl MovementStrategy for AvoidSaplings {
+    fn name(&self) -> &'static str {
+        "avoid-saplings"
+    }
+
+    fn filter_steps(&self, forest: &Forest, _from: usize, candidates: &mut Neighbours) {
+        candidates.retain(|c| forest.tree_kind_at(c) != TreeKind::Sapling);
+    }
+}
+
+pub struct Hunt {
+    pub sight: usize,
+    pub chase: u32,
+}
+
+impl Hunt {
+    fn nearest_prey(&self, forest: &Forest, from: usize) -> Option<usize> {
+        find_nearest(forest, from, self.sight, |index| {
+            (forest.cell(index) & JACK_MASK) > 0
+        })
+    }
+}
+
+impl MovementStrategy for Hunt {
+    fn name(&self) -> &'static str {
+        "hunt"
+    }
+
+    fn filter_steps(&self, forest: &Forest, from: usize, candidates: &mut Neighbours) {
+        match self.nearest_prey(forest, from) {
+            Some(prey) => step_towards(forest, prey, candidates),
+            None => {
+                if candidates
+                    .iter()
+                    .any(|&c| forest.tree_kind_at(c) == TreeKind::Elder)
+                {
+                    candidates.retain(|c| forest.tree_kind_at(c) == TreeKind::Elder);
+                }
+            }
+        }
+    }
+
+    fn steps_per_month(&self, forest: &Forest, from: usize, default: u32) -> u32 {
+        match self.nearest_prey(forest, from) {
+            Some(_) => self.chase,
+            None => default,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub enum JackMovement {
+    #[default]
+    RandomWalk,
+    Greedy {
+        sight: usize,
+    },
+    AvoidBears,
+    AvoidSaplings,
+    Custom(Arc<dyn MovementStrategy>),
+}
+
+impl JackMovement {
+    pub fn build(&self) -> Arc<dyn MovementStrategy> {
+        match self {
+            JackMovement::RandomWalk => Arc::new(RandomWalk),
+            &JackMovement::Greedy { sight } => Arc::new(GreedyHarvest { sight }),
+            JackMovement::AvoidBears => Arc::new(AvoidBears),
+            JackMovement::AvoidSaplings => Arc::new(AvoidSaplings),
+            JackMovement::Custom(strategy) => strategy.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for JackMovement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JackMovement({})", self)
+    }
+}
+
+impl PartialEq for JackMovement {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (JackMovement::Custom(a), JackMovement::Custom(b)) => Arc::ptr_eq(a, b),
+            (JackMovement::Custom(_), _) | (_, JackMovement::Custom(_)) => false,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for JackMovement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JackMovement::RandomWalk => write!(f, "random"),
+            JackMovement::Greedy { sight } => write!(f, "greedy:{}", sight),
+            JackMovement::AvoidBears => write!(f, "avoid-bears"),
+            JackMovement::AvoidSaplings => write!(f, "avoid-saplings"),
+            JackMovement::Custom(strategy) => write!(f, "custom:{}", strategy.name()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMovementError(String);
+
+impl fmt::Display for ParseMovementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid movement strategy: {}", self.0)
+    }
+}
+
+impl Error for ParseMovementError {}
+
+impl FromStr for JackMovement {
+    type Err = ParseMovementError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let movement = match s.split_once(':') {
+            None if s == "random" => JackMovement::RandomWalk,
+            None if s == "greedy" => JackMovement::Greedy {
+                sight: JACK_SIGHT_RADIUS,
+            },
+            None if s == "avoid-bears" => JackMovement::AvoidBears,
+            None if s == "avoid-saplings" => JackMovement::AvoidSaplings,
+            Some(("greedy", sight)) => JackMovement::Greedy {
+                sight: sight
+                    .parse()
+                    .ok()
+                    .filter(|&sight| sight <= MAX_SIGHT_RADIUS)
+                    .ok_or_else(|| ParseMovementError(s.to_string()))?,
+            },
+            _ => return Err(ParseMovementError(s.to_string())),
+        };
+
+        Ok(movement)
+    }
+}
+
+#[derive(Clone, Default)]
+pub enum BearMovement {
+    #[default]
+    RandomWalk,
+    Hunt {
+        sight: usize,
+        chase: u32,
+    },
+    Custom(Arc<dyn MovementStrategy>),
+}
+
+impl BearMovement {
+    pub fn build(&self) -> Arc<dyn MovementStrategy> {
+        match self {
+            BearMovement::RandomWalk => Arc::new(RandomWalk),
+            &BearMovement::Hunt { sight, chase } => Arc::new(Hunt { sight, chase }),
+            BearMovement::Custom(strategy) => strategy.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for BearMovement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BearMovement({})", self)
+    }
+}
+
+impl PartialEq for BearMovement {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BearMovement::Custom(a), BearMovement::Custom(b)) => Arc::ptr_eq(a, b),
+            (BearMovement::Custom(_), _) | (_, BearMovement::Custom(_)) => false,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for BearMovement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BearMovement::RandomWalk => write!(f, "random"),
+            BearMovement::Hunt { sight, chase } => write!(f, "hunt:{}:{}", sight, chase),
+            BearMovement::Custom(strategy) => write!(f, "custom:{}", strategy.name()),
+        }
+    }
+}
+
+impl FromStr for BearMovement {
+    type Err = ParseMovementError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let invalid = || ParseMovementError(s.to_string());
+
+        let movement = match parts.next() {
+            Some("random") => BearMovement::RandomWalk,
+            Some("hunt") => {
+                let sight = match parts.next() {
+                    Some(sight) => sight.parse().map_err(|_| invalid())?,
+                    None => BEAR_SIGHT_RADIUS,
+                };
+                let chase = match parts.next() {
+                    Some(chase) => chase.parse().map_err(|_| invalid())?,
+                    None => BEAR_CHASE_STEPS,
+                };
+                if sight > MAX_SIGHT_RADIUS || chase > MAX_CHASE_STEPS {
+                    return Err(invalid());
+                }
+                BearMovement::Hunt { sight, chase }
+            }
+            _ => return Err(invalid()),
+        };
+
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(movement)
+    }
+}
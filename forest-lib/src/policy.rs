@@ -0,0 +1,303 @@
+use std::{error::Error, fmt, str::FromStr, sync::Arc};
+
+use crate::consts::{MAX_POLICY_PERCENT, MAX_POLICY_QUOTA};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PolicyContext {
+    pub year: u32,
+    pub cells: usize,
+    pub trees: usize,
+    pub jacks: usize,
+    pub bears: usize,
+    pub lumber: u32,
+    pub mauls: u32,
+    /// Profit, wages and affordable hires are only known when the economy rule is enabled.
+    pub profit: Option<i64>,
+    pub wages: i64,
+    pub affordable_hires: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Adjustment {
+    None,
+    Hire(usize),
+    Cull(usize),
+}
+
+impl Adjustment {
+    fn towards(current: usize, target: usize) -> Self {
+        if target > current {
+            Adjustment::Hire(target - current)
+        } else if target < current {
+            Adjustment::Cull(current - target)
+        } else {
+            Adjustment::None
+        }
+    }
+}
+
+pub trait YearlyPolicy: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn adjust_jacks(&self, context: &PolicyContext) -> Adjustment;
+    fn adjust_bears(&self, context: &PolicyContext) -> Adjustment;
+}
+
+pub struct ClassicPolicy;
+
+impl ClassicPolicy {
+    fn classic_bears(context: &PolicyContext) -> Adjustment {
+        if context.mauls == 0 {
+            Adjustment::Hire(1)
+        } else if context.bears > 1 {
+            Adjustment::Cull(1)
+        } else {
+            Adjustment::None
+        }
+    }
+}
+
+impl YearlyPolicy for ClassicPolicy {
+    fn name(&self) -> &'static str {
+        "classic"
+    }
+
+    fn adjust_jacks(&self, context: &PolicyContext) -> Adjustment {
+        if context.lumber as usize > context.jacks {
+            let excess_lumber = context.lumber as usize - context.jacks;
+            Adjustment::Hire(excess_lumber / 10)
+        } else if context.jacks > 1 {
+            Adjustment::Cull(1)
+        } else {
+            Adjustment::None
+        }
+    }
+
+    fn adjust_bears(&self, context: &PolicyContext) -> Adjustment {
+        Self::classic_bears(context)
+    }
+}
+
+pub struct ProfitPolicy;
+
+impl YearlyPolicy for ProfitPolicy {
+    fn name(&self) -> &'static str {
+        "profit"
+    }
+
+    fn adjust_jacks(&self, context: &PolicyContext) -> Adjustment {
+        match context.profit {
+            Some(profit) if profit > 0 => Adjustment::Hire(context.affordable_hires),
+            Some(profit) if profit < 0 && context.jacks > 1 => {
+                let average_wage = i64::max(context.wages / context.jacks as i64, 1);
+                let layoffs = (-profit / average_wage) as usize + 1;
+                Adjustment::Cull(usize::min(layoffs, context.jacks - 1))
+            }
+            _ => Adjustment::None,
+        }
+    }
+
+    fn adjust_bears(&self, context: &PolicyContext) -> Adjustment {
+        ClassicPolicy::classic_bears(context)
+    }
+}
+
+pub struct FixedQuotaPolicy {
+    pub jacks: usize,
+    pub bears: usize,
+}
+
+impl YearlyPolicy for FixedQuotaPolicy {
+    fn name(&self) -> &'static str {
+        "fixed-quota"
+    }
+
+    fn adjust_jacks(&self, context: &PolicyContext) -> Adjustment {
+        Adjustment::towards(context.jacks, self.jacks)
+    }
+
+    fn adjust_bears(&self, context: &PolicyContext) -> Adjustment {
+        Adjustment::towards(context.bears, self.bears)
+    }
+}
+
+pub struct TreeDensityPolicy {
+    pub jacks_per_hundred_trees: usize,
+}
+
+impl YearlyPolicy for TreeDensityPolicy {
+    fn name(&self) -> &'static str {
+        "tree-density"
+    }
+
+    fn adjust_jacks(&self, context: &PolicyContext) -> Adjustment {
+        let target = context.trees.saturating_mul(self.jacks_per_hundred_trees) / 100;
+        Adjustment::towards(context.jacks, usize::max(target, 1))
+    }
+
+    fn adjust_bears(&self, context: &PolicyContext) -> Adjustment {
+        ClassicPolicy::classic_bears(context)
+    }
+}
+
+pub struct SustainableYieldPolicy {
+    pub yield_percent: usize,
+}
+
+impl YearlyPolicy for SustainableYieldPolicy {
+    fn name(&self) -> &'static str {
+        "sustainable-yield"
+    }
+
+    fn adjust_jacks(&self, context: &PolicyContext) -> Adjustment {
+        let target_lumber = context.trees.saturating_mul(self.yield_percent) / 100;
+        let lumber_per_jack = usize::max(context.lumber as usize / usize::max(context.jacks, 1), 1);
+        let target = usize::max(target_lumber / lumber_per_jack, 1);
+        Adjustment::towards(context.jacks, target)
+    }
+
+    fn adjust_bears(&self, context: &PolicyContext) -> Adjustment {
+        ClassicPolicy::classic_bears(context)
+    }
+}
+
+pub struct NoInterventionPolicy;
+
+impl YearlyPolicy for NoInterventionPolicy {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    fn adjust_jacks(&self, _context: &PolicyContext) -> Adjustment {
+        Adjustment::None
+    }
+
+    fn adjust_bears(&self, _context: &PolicyContext) -> Adjustment {
+        Adjustment::None
+    }
+}
+
+#[derive(Clone, Default)]
+pub enum PolicyKind {
+    #[default]
+    Classic,
+    Profit,
+    FixedQuota {
+        jacks: usize,
+        bears: usize,
+    },
+    TreeDensity {
+        jacks_per_hundred_trees: usize,
+    },
+    SustainableYield {
+        yield_percent: usize,
+    },
+    NoIntervention,
+    Custom(Arc<dyn YearlyPolicy>),
+}
+
+impl PolicyKind {
+    pub fn build(&self) -> Arc<dyn YearlyPolicy> {
+        match self {
+            PolicyKind::Classic => Arc::new(ClassicPolicy),
+            PolicyKind::Profit => Arc::new(ProfitPolicy),
+            &PolicyKind::FixedQuota { jacks, bears } => Arc::new(FixedQuotaPolicy { jacks, bears }),
+            &PolicyKind::TreeDensity {
+                jacks_per_hundred_trees,
+            } => Arc::new(TreeDensityPolicy {
+                jacks_per_hundred_trees,
+            }),
+            &PolicyKind::SustainableYield { yield_percent } => {
+                Arc::new(SustainableYieldPolicy { yield_percent })
+            }
+            PolicyKind::NoIntervention => Arc::new(NoInterventionPolicy),
+            PolicyKind::Custom(policy) => policy.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for PolicyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PolicyKind({})", self)
+    }
+}
+
+impl PartialEq for PolicyKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PolicyKind::Custom(a), PolicyKind::Custom(b)) => Arc::ptr_eq(a, b),
+            (PolicyKind::Custom(_), _) | (_, PolicyKind::Custom(_)) => false,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for PolicyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyKind::Classic => write!(f, "classic"),
+            PolicyKind::Profit => write!(f, "profit"),
+            PolicyKind::FixedQuota { jacks, bears } => write!(f, "fixed-quota:{}:{}", jacks, bears),
+            PolicyKind::TreeDensity {
+                jacks_per_hundred_trees,
+            } => write!(f, "tree-density:{}", jacks_per_hundred_trees),
+            PolicyKind::SustainableYield { yield_percent } => {
+                write!(f, "sustainable-yield:{}", yield_percent)
+            }
+            PolicyKind::NoIntervention => write!(f, "none"),
+            PolicyKind::Custom(policy) => write!(f, "custom:{}", policy.name()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePolicyError(String);
+
+impl fmt::Display for ParsePolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid policy: {}", self.0)
+    }
+}
+
+impl Error for ParsePolicyError {}
+
+impl FromStr for PolicyKind {
+    type Err = ParsePolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let name = parts.next().unwrap_or_default();
+        let mut param = |default: usize, max: usize| -> Result<usize, ParsePolicyError> {
+            match parts.next() {
+                Some(value) => value
+                    .parse()
+                    .ok()
+                    .filter(|&value| value <= max)
+                    .ok_or_else(|| ParsePolicyError(s.to_string())),
+                None => Ok(default),
+            }
+        };
+
+        let kind = match name {
+            "classic" => PolicyKind::Classic,
+            "profit" => PolicyKind::Profit,
+            "fixed-quota" => PolicyKind::FixedQuota {
+                jacks: param(50, MAX_POLICY_QUOTA)?,
+                bears: param(10, MAX_POLICY_QUOTA)?,
+            },
+            "tree-density" => PolicyKind::TreeDensity {
+                jacks_per_hundred_trees: param(10, MAX_POLICY_PERCENT)?,
+            },
+            "sustainable-yield" => PolicyKind::SustainableYield {
+                yield_percent: param(10, MAX_POLICY_PERCENT)?,
+            },
+            "none" => PolicyKind::NoIntervention,
+            _ => return Err(ParsePolicyError(s.to_string())),
+        };
+
+        if parts.next().is_some() {
+            return Err(ParsePolicyError(s.to_string()));
+        }
+
+        Ok(kind)
+    }
+}
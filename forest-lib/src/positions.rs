@@ -0,0 +1,299 @@
+use crate::cell::Cell;
+use crate::consts::{BEAR_MASK, CHUNK_SIZE, ENTITY_MASK, JACK_MASK, TREE_MASK};
+
+const WORD_BITS: usize = 64;
+const BLOCK_WORDS: usize = CHUNK_SIZE / WORD_BITS;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Block {
+    words: [u64; BLOCK_WORDS],
+    len: usize,
+}
+
+/// An ordered set of cell indices backed by a bitset split into blocks that line up with
+/// the map's chunks. Empty blocks are not allocated, and a Fenwick tree over the
+/// per-block counts finds the n-th member (or non-member) without a scan.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PositionSet {
+    blocks: Vec<Option<Box<Block>>>,
+    counts: Vec<u32>,
+    capacity: usize,
+    len: usize,
+}
+
+impl PositionSet {
+    pub fn new(capacity: usize) -> Self {
+        let num_blocks = capacity.div_ceil(CHUNK_SIZE);
+        Self {
+            blocks: vec![None; num_blocks],
+            counts: vec![0; num_blocks + 1],
+            capacity,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Bytes held on the heap: the block table, the counts and every allocated block.
+    pub fn heap_size(&self) -> usize {
+        let blocks = self.blocks.iter().filter(|block| block.is_some()).count();
+        self.blocks.capacity() * std::mem::size_of::<Option<Box<Block>>>()
+            + self.counts.capacity() * std::mem::size_of::<u32>()
+            + blocks * std::mem::size_of::<Block>()
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.blocks[index / CHUNK_SIZE].as_ref().is_some_and(|block| {
+            let bit = index % CHUNK_SIZE;
+            (block.words[bit / WORD_BITS] >> (bit % WORD_BITS)) & 1 == 1
+        })
+    }
+
+    pub fn insert(&mut self, index: usize) -> bool {
+        if self.contains(index) {
+            return false;
+        }
+
+        let block = self.blocks[index / CHUNK_SIZE].get_or_insert_with(|| {
+            Box::new(Block {
+                words: [0; BLOCK_WORDS],
+                len: 0,
+            })
+        });
+        let bit = index % CHUNK_SIZE;
+        block.words[bit / WORD_BITS] |= 1 << (bit % WORD_BITS);
+        block.len += 1;
+
+        self.add_count(index / CHUNK_SIZE, 1);
+        self.len += 1;
+        true
+    }
+
+    pub fn remove(&mut self, index: usize) -> bool {
+        if !self.contains(index) {
+            return false;
+        }
+
+        let slot = &mut self.blocks[index / CHUNK_SIZE];
+        if let Some(block) = slot {
+            let bit = index % CHUNK_SIZE;
+            block.words[bit / WORD_BITS] &= !(1 << (bit % WORD_BITS));
+            block.len -= 1;
+
+            if block.len == 0 {
+                *slot = None;
+            }
+        }
+
+        self.add_count(index / CHUNK_SIZE, -1);
+        self.len -= 1;
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.iter_mut().for_each(|block| *block = None);
+        self.counts.iter_mut().for_each(|count| *count = 0);
+        self.len = 0;
+    }
+
+    /// Iterates the members in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(b, block)| block.as_ref().map(|block| (b, block)))
+            .flat_map(|(b, block)| {
+                block
+                    .words
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &word)| word != 0)
+                    .flat_map(move |(w, &word)| {
+                        BitIter(word).map(move |bit| b * CHUNK_SIZE + w * WORD_BITS + bit)
+                    })
+            })
+    }
+
+    pub fn to_vec(&self) -> Vec<usize> {
+        self.iter().collect()
+    }
+
+    /// Returns the `n`-th smallest member.
+    pub fn nth(&self, n: usize) -> Option<usize> {
+        if n >= self.len {
+            return None;
+        }
+
+        let (b, mut remaining) = self.find_block(n, |count, _| count);
+        let block = self.blocks[b].as_ref()?;
+
+        for (w, &word) in block.words.iter().enumerate() {
+            let count = word.count_ones() as usize;
+            if remaining < count {
+                return Some(b * CHUNK_SIZE + w * WORD_BITS + select_bit(word, remaining));
+            }
+            remaining -= count;
+        }
+
+        None
+    }
+
+    /// Returns the `n`-th smallest index below `capacity` that is not a member.
+    pub fn nth_absent(&self, n: usize) -> Option<usize> {
+        if n >= self.capacity - self.len {
+            return None;
+        }
+
+        let (b, mut remaining) = self.find_block(n, |count, blocks| blocks * CHUNK_SIZE - count);
+        let Some(block) = &self.blocks[b] else {
+            return Some(b * CHUNK_SIZE + remaining);
+        };
+
+        for (w, &word) in block.words.iter().enumerate() {
+            let count = word.count_zeros() as usize;
+            if remaining < count {
+                return Some(b * CHUNK_SIZE + w * WORD_BITS + select_bit(!word, remaining));
+            }
+            remaining -= count;
+        }
+
+        None
+    }
+
+    fn add_count(&mut self, block: usize, delta: i32) {
+        let mut node = block + 1;
+        while node < self.counts.len() {
+            self.counts[node] = (self.counts[node] as i32 + delta) as u32;
+            node += node & node.wrapping_neg();
+        }
+    }
+
+    /// Walks the Fenwick tree to find the block holding the `n`-th counted bit, where
+    /// `count_of` turns a node's member count and block span into the count being searched.
+    fn find_block<F>(&self, n: usize, count_of: F) -> (usize, usize)
+    where
+        F: Fn(usize, usize) -> usize,
+    {
+        let num_blocks = self.blocks.len();
+        let mut position = 0;
+        let mut remaining = n;
+        let mut step = if num_blocks == 0 {
+            0
+        } else {
+            1 << num_blocks.ilog2()
+        };
+
+        while step > 0 {
+            let next = position + step;
+            if next <= num_blocks {
+                let count = count_of(self.counts[next] as usize, step);
+                if count <= remaining {
+                    position = next;
+                    remaining -= count;
+                }
+            }
+            step >>= 1;
+        }
+
+        (position, remaining)
+    }
+}
+
+struct BitIter(u64);
+
+impl Iterator for BitIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let bit = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+fn select_bit(word: u64, n: usize) -> usize {
+    BitIter(word).nth(n).unwrap_or(WORD_BITS)
+}
+
+/// Positions of every tree, jack and bear, plus every occupied cell, kept in step with the map.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntityIndex {
+    pub trees: PositionSet,
+    pub jacks: PositionSet,
+    pub bears: PositionSet,
+    pub occupied: PositionSet,
+}
+
+impl EntityIndex {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            trees: PositionSet::new(capacity),
+            jacks: PositionSet::new(capacity),
+            bears: PositionSet::new(capacity),
+            occupied: PositionSet::new(capacity),
+        }
+    }
+
+    pub fn update(&mut self, index: usize, old: Cell, new: Cell) {
+        Self::update_set(&mut self.trees, index, old & TREE_MASK, new & TREE_MASK);
+        Self::update_set(&mut self.jacks, index, old & JACK_MASK, new & JACK_MASK);
+        Self::update_set(&mut self.bears, index, old & BEAR_MASK, new & BEAR_MASK);
+        Self::update_set(&mut self.occupied, index, old & ENTITY_MASK, new & ENTITY_MASK);
+    }
+
+    pub fn rebuild<I>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = Cell>,
+    {
+        self.trees.clear();
+        self.jacks.clear();
+        self.bears.clear();
+        self.occupied.clear();
+
+        for (index, cell) in cells.into_iter().enumerate() {
+            self.update(index, 0, cell);
+        }
+    }
+
+    /// Returns the `n`-th cell holding no tree, jack or bear.
+    pub fn nth_empty(&self, n: usize) -> Option<usize> {
+        self.occupied.nth_absent(n)
+    }
+
+    pub fn empty_len(&self) -> usize {
+        self.occupied.capacity() - self.occupied.len()
+    }
+
+    pub fn heap_size(&self) -> usize {
+        self.trees.heap_size()
+            + self.jacks.heap_size()
+            + self.bears.heap_size()
+            + self.occupied.heap_size()
+    }
+
+    fn update_set(set: &mut PositionSet, index: usize, old: Cell, new: Cell) {
+        match (old > 0, new > 0) {
+            (false, true) => {
+                set.insert(index);
+            }
+            (true, false) => {
+                set.remove(index);
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,3 +1,4 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Random {
     seed: u64,
 }
@@ -7,6 +8,10 @@ impl Random {
         Self { seed }
     }
 
+    pub fn state(&self) -> u64 {
+        self.seed
+    }
+
     pub fn next(&mut self) -> u64 {
         let next = self.seed;
 
@@ -17,7 +22,7 @@ impl Random {
         next
     }
 
-    pub fn choose<T: Copy>(&mut self, list: &Vec<T>) -> Option<T> {
+    pub fn choose<T: Copy>(&mut self, list: &[T]) -> Option<T> {
         if !list.is_empty() {
             let next = self.next() as usize;
             let idx = next % list.len();
@@ -26,14 +31,4 @@ impl Random {
             None
         }
     }
-
-    pub fn shuffle<'a, T>(&mut self, list: &'a mut Vec<T>) -> &'a Vec<T> {
-        for _ in 0..list.len() {
-            let idx = self.next() as usize % list.len();
-            let t = list.swap_remove(idx);
-            list.push(t);
-        }
-
-        list
-    }
 }
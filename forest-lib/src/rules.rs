@@ -0,0 +1,93 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::consts::{STARTING_BEARS, STARTING_JACKS, STARTING_TREES};
+use crate::movement::{BearMovement, JackMovement};
+use crate::policy::PolicyKind;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rules {
+    /// Bears age, eat, breed and starve instead of following the yearly maul rule.
+    pub bear_lifecycle: bool,
+    /// Harvested lumber is stockpiled and sold, and jacks are hired or laid off based on profit.
+    pub economy: bool,
+    pub policy: PolicyKind,
+    pub jack_movement: JackMovement,
+    pub bear_movement: BearMovement,
+    pub density: Density,
+    /// Grows trees on this many threads when above one. Results are deterministic for a
+    /// given seed and thread count, but differ from the sequential phase. Ignored unless
+    /// built with the `parallel` feature.
+    pub tree_threads: usize,
+}
+
+impl Rules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The share of cells given a tree, jack or bear when the forest is created. Large
+/// regional maps want these low so that most of the map's chunks stay empty.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Density {
+    pub trees: f32,
+    pub jacks: f32,
+    pub bears: f32,
+}
+
+impl Default for Density {
+    fn default() -> Self {
+        Self {
+            trees: STARTING_TREES,
+            jacks: STARTING_JACKS,
+            bears: STARTING_BEARS,
+        }
+    }
+}
+
+impl Density {
+    /// Whether every share is between zero and one. NaN is not.
+    pub fn is_valid(&self) -> bool {
+        [self.trees, self.jacks, self.bears]
+            .iter()
+            .all(|value| (0.0..=1.0).contains(value))
+    }
+}
+
+impl fmt::Display for Density {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.trees, self.jacks, self.bears)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDensityError(String);
+
+impl fmt::Display for ParseDensityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid density: {}", self.0)
+    }
+}
+
+impl Error for ParseDensityError {}
+
+impl FromStr for Density {
+    type Err = ParseDensityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split(':')
+            .map(|value| value.parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ParseDensityError(s.to_string()))?;
+
+        match values[..] {
+            [trees, jacks, bears] => Ok(Density {
+                trees,
+                jacks,
+                bears,
+            }),
+            _ => Err(ParseDensityError(s.to_string())),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct YearlyStats {
+    pub year: u32,
+    pub trees: u32,
+    pub jacks: u32,
+    pub bears: u32,
+    pub lumber: u32,
+    pub mauls: u32,
+    pub bear_births: u32,
+    pub bear_deaths: u32,
+    pub jacks_hired: u32,
+    pub jacks_fired: u32,
+    pub stockpile: u64,
+    pub lumber_sold: u64,
+    pub revenue: i64,
+    pub wages: i64,
+    pub profit: i64,
+    pub funds: i64,
+}
+
+impl YearlyStats {
+    pub fn new(year: u32) -> Self {
+        Self {
+            year,
+            ..Self::default()
+        }
+    }
+}
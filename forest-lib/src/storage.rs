@@ -0,0 +1,169 @@
+use std::ops::Index;
+
+use crate::cell::Cell;
+use crate::consts::CHUNK_SIZE;
+
+static EMPTY_CELL: Cell = 0;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Chunk {
+    cells: [Cell; CHUNK_SIZE],
+    occupied: usize,
+}
+
+/// Writes `cell` into a chunk slot, allocating the chunk on first use and freeing it
+/// again once its last occupied cell is cleared. Returns the previous value.
+fn set_in_slot(slot: &mut Option<Box<Chunk>>, offset: usize, cell: Cell) -> Cell {
+    let chunk = match slot {
+        Some(chunk) => chunk,
+        None if cell == 0 => return 0,
+        None => slot.insert(Box::new(Chunk {
+            cells: [0; CHUNK_SIZE],
+            occupied: 0,
+        })),
+    };
+
+    let old = std::mem::replace(&mut chunk.cells[offset], cell);
+    match (old > 0, cell > 0) {
+        (false, true) => chunk.occupied += 1,
+        (true, false) => chunk.occupied -= 1,
+        _ => {}
+    }
+
+    if chunk.occupied == 0 {
+        *slot = None;
+    }
+
+    old
+}
+
+/// Cells stored in chunks of `CHUNK_SIZE` consecutive indices. A chunk holding only
+/// empty cells is not allocated, so sparse maps only pay for the chunks in use.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChunkedMap {
+    chunks: Vec<Option<Box<Chunk>>>,
+    len: usize,
+}
+
+impl ChunkedMap {
+    pub fn new(len: usize) -> Self {
+        Self {
+            chunks: vec![None; len.div_ceil(CHUNK_SIZE)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Cell {
+        self[index]
+    }
+
+    /// Writes `cell` at `index` and returns the value it replaced.
+    pub fn set(&mut self, index: usize, cell: Cell) -> Cell {
+        assert!(index < self.len, "cell {} out of bounds", index);
+        set_in_slot(&mut self.chunks[index / CHUNK_SIZE], index % CHUNK_SIZE, cell)
+    }
+
+    /// Iterates every cell in index order, including those in unallocated chunks.
+    pub fn iter(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.chunks.iter().enumerate().flat_map(move |(c, chunk)| {
+            let cells = usize::min(CHUNK_SIZE, self.len - c * CHUNK_SIZE);
+            (0..cells).map(move |i| chunk.as_ref().map_or(0, |chunk| chunk.cells[i]))
+        })
+    }
+
+    /// Iterates the index and contents of every non-empty cell in index order, skipping
+    /// unallocated chunks.
+    pub fn occupied(&self) -> impl Iterator<Item = (usize, Cell)> + '_ {
+        self.chunks
+            .iter()
+            .enumerate()
+            .filter_map(|(c, chunk)| chunk.as_ref().map(|chunk| (c * CHUNK_SIZE, chunk)))
+            .flat_map(|(first, chunk)| {
+                chunk
+                    .cells
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &cell)| cell != 0)
+                    .map(move |(i, &cell)| (first + i, cell))
+            })
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn active_chunk_count(&self) -> usize {
+        self.chunks.iter().filter(|chunk| chunk.is_some()).count()
+    }
+
+    /// Bytes held on the heap: the chunk table plus every allocated chunk.
+    pub fn heap_size(&self) -> usize {
+        self.chunks.capacity() * std::mem::size_of::<Option<Box<Chunk>>>()
+            + self.active_chunk_count() * std::mem::size_of::<Chunk>()
+    }
+
+    /// Splits the map into independently writable windows over the given chunk ranges,
+    /// which must be ascending and must not overlap.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn windows_mut(&mut self, ranges: &[(usize, usize)]) -> Vec<MapWindow<'_>> {
+        let mut rest: &mut [Option<Box<Chunk>>] = &mut self.chunks;
+        let mut rest_start = 0;
+        let mut windows = vec![];
+
+        for &(start, end) in ranges {
+            let (_, tail) = rest.split_at_mut(start - rest_start);
+            let (chunks, tail) = tail.split_at_mut(end - start);
+            rest = tail;
+            rest_start = end;
+
+            windows.push(MapWindow {
+                chunks,
+                first_chunk: start,
+            });
+        }
+
+        windows
+    }
+}
+
+impl Index<usize> for ChunkedMap {
+    type Output = Cell;
+
+    fn index(&self, index: usize) -> &Cell {
+        assert!(index < self.len, "cell {} out of bounds", index);
+        match &self.chunks[index / CHUNK_SIZE] {
+            Some(chunk) => &chunk.cells[index % CHUNK_SIZE],
+            None => &EMPTY_CELL,
+        }
+    }
+}
+
+/// A mutable view over a run of chunks, handed to a worker thread.
+#[cfg(feature = "parallel")]
+pub(crate) struct MapWindow<'a> {
+    chunks: &'a mut [Option<Box<Chunk>>],
+    first_chunk: usize,
+}
+
+#[cfg(feature = "parallel")]
+impl MapWindow<'_> {
+    pub(crate) fn get(&self, index: usize) -> Cell {
+        match &self.chunks[index / CHUNK_SIZE - self.first_chunk] {
+            Some(chunk) => chunk.cells[index % CHUNK_SIZE],
+            None => 0,
+        }
+    }
+
+    pub(crate) fn set(&mut self, index: usize, cell: Cell) -> Cell {
+        let slot = &mut self.chunks[index / CHUNK_SIZE - self.first_chunk];
+        set_in_slot(slot, index % CHUNK_SIZE, cell)
+    }
+}
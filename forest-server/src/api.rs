@@ -0,0 +1,342 @@
+use std::{
+    fmt,
+    sync::{Arc, PoisonError},
+};
+
+use forest_lib::{forest::Forest, rules::Rules};
+use forest_session::{
+    details,
+    registry::{ForestId, Registry},
+    rules::RuleSettings,
+    statistics::{self, YearRange},
+    DEFAULT_HEIGHT, DEFAULT_SEED, DEFAULT_WIDTH, MAX_STEP_MONTHS,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::{State, MAX_FORESTS, MAX_FOREST_CELLS};
+
+const INDEX_HTML: &str = include_str!("index.html");
+
+pub enum ApiError {
+    /// No such route, or no forest with the requested id.
+    NotFound(String),
+    /// The request was understood but its body or arguments were rejected.
+    BadRequest(String),
+    /// An earlier request panicked while holding a lock this one needs.
+    Internal(String),
+}
+
+impl ApiError {
+    fn status(&self) -> u16 {
+        match self {
+            ApiError::NotFound(_) => 404,
+            ApiError::BadRequest(_) => 400,
+            ApiError::Internal(_) => 500,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotFound(message)
+            | ApiError::BadRequest(message)
+            | ApiError::Internal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        ApiError::BadRequest(message)
+    }
+}
+
+impl<T> From<PoisonError<T>> for ApiError {
+    fn from(_: PoisonError<T>) -> Self {
+        ApiError::Internal("an earlier request failed while changing this state".to_string())
+    }
+}
+
+/// The body of `POST /forests`. Anything left out takes the GUI's defaults.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CreateForest {
+    name: Option<String>,
+    seed: Option<u64>,
+    width: Option<usize>,
+    height: Option<usize>,
+    rules: Option<RuleSettings>,
+}
+
+#[derive(Deserialize)]
+struct Step {
+    months: u32,
+}
+
+#[derive(Deserialize)]
+struct Start {
+    speed: u32,
+}
+
+/// Answers one request. Frames are streamed separately, by `viewers::listen`.
+pub fn handle(state: &Arc<State>, mut request: Request) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+
+    if let (Method::Get, []) = (request.method(), &segments[..]) {
+        let content_type = Header::from_bytes("Content-Type", "text/html; charset=utf-8").unwrap();
+        let page = INDEX_HTML.replace("{{FRAMES_PORT}}", &state.frames_port.to_string());
+        let _ = request.respond(Response::from_string(page).with_header(content_type));
+        return;
+    }
+
+    match route(state, &mut request, &segments, query) {
+        Ok(body) => {
+            let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+            let _ = request.respond(Response::from_string(body.to_string()).with_header(content_type));
+        }
+        Err(error) => respond_error(request, error),
+    }
+}
+
+fn route(
+    state: &Arc<State>,
+    request: &mut Request,
+    segments: &[&str],
+    query: &str,
+) -> Result<Value, ApiError> {
+    let method = request.method().clone();
+
+    match (method, segments) {
+        (Method::Get, ["forests"]) => {
+            let entries: Vec<_> = state.forests.lock()?.entries().cloned().collect();
+            // A forest broken by an earlier failure is left out rather than failing the list.
+            let summaries: Vec<_> = entries
+                .iter()
+                .filter_map(|entry| Some(entry.session.lock().ok()?.summary()))
+                .collect();
+            to_json(&summaries)
+        }
+        (Method::Post, ["forests"]) => {
+            let create: CreateForest = read_body(request)?;
+            // Checked again on insert, but failing here saves building a forest for nothing.
+            check_room(&*state.forests.lock()?)?;
+
+            let (width, height) = (
+                create.width.unwrap_or(DEFAULT_WIDTH),
+                create.height.unwrap_or(DEFAULT_HEIGHT),
+            );
+            if width.checked_mul(height).is_none_or(|cells| cells > MAX_FOREST_CELLS) {
+                return Err(ApiError::BadRequest(format!(
+                    "a {}x{} forest has more than {} cells",
+                    width, height, MAX_FOREST_CELLS
+                )));
+            }
+
+            let rules = match create.rules {
+                Some(rules) => rules.into_rules(Rules::default())?,
+                None => Rules::default(),
+            };
+            let forest =
+                Forest::try_with_rules(create.seed.unwrap_or(DEFAULT_SEED), width, height, rules)
+                    .map_err(|err| ApiError::BadRequest(format!("{}", err)))?;
+
+            let entry = {
+                let mut forests = state.forests.lock()?;
+                check_room(&forests)?;
+                forests.insert(create.name, forest)
+            };
+            let info = entry.session.lock()?.resync();
+            to_json(&info)
+        }
+        (Method::Get, ["forests", id]) => {
+            let entry = state.entry(parse_id(id)?)?;
+            let info = entry.session.lock()?.snapshot();
+            to_json(&info)
+        }
+        (Method::Delete, ["forests", id]) => {
+            let id = parse_id(id)?;
+            state.forests.lock()?.remove(id).map_err(ApiError::NotFound)?;
+            state.viewers.close(id);
+            Ok(Value::Null)
+        }
+        (Method::Post, ["forests", id, "step"]) => {
+            let id = parse_id(id)?;
+            let entry = state.entry(id)?;
+            let step: Step = read_body(request)?;
+            if step.months > MAX_STEP_MONTHS {
+                return Err(ApiError::BadRequest(format!(
+                    "cannot step more than {} months at once",
+                    MAX_STEP_MONTHS
+                )));
+            }
+
+            let frame = {
+                let mut session = entry.session.lock()?;
+                session.record();
+                for _ in 0..step.months {
+                    session.forest.update();
+                }
+                session.frame()
+            };
+
+            state.viewers.broadcast(id, &frame);
+            to_json(&frame)
+        }
+        (Method::Post, ["forests", id, "start"]) => {
+            let id = parse_id(id)?;
+            let entry = state.entry(id)?;
+            let start: Start = read_body(request)?;
+            let viewing = Arc::clone(state);
+            entry.playback.start(start.speed, entry.session, move |frame| {
+                viewing.viewers.broadcast(id, &frame);
+                true
+            })?;
+            Ok(Value::Null)
+        }
+        (Method::Post, ["forests", id, "pause"]) => {
+            state.entry(parse_id(id)?)?.playback.pause();
+            Ok(Value::Null)
+        }
+        (Method::Get, ["forests", id, "cells", x, y]) => {
+            let entry = state.entry(parse_id(id)?)?;
+            let (x, y) = (parse_number(x)?, parse_number(y)?);
+            let session = entry.session.lock()?;
+            match session.forest.inspect(x, y) {
+                Some(info) => to_json(&details::cell_details(&info)),
+                None => Err(ApiError::NotFound(format!("({}, {}) is outside the map", x, y))),
+            }
+        }
+        (Method::Get, ["forests", id, "rules"]) => {
+            let entry = state.entry(parse_id(id)?)?;
+            let session = entry.session.lock()?;
+            to_json(&RuleSettings::from(&session.forest.rules))
+        }
+        (Method::Put, ["forests", id, "rules"]) => {
+            let id = parse_id(id)?;
+            let entry = state.entry(id)?;
+            let rules: RuleSettings = read_body(request)?;
+            let frame = {
+                let mut session = entry.session.lock()?;
+                let rules = rules.into_rules(session.forest.rules.clone())?;
+                session
+                    .change(|forest| forest.set_rules(rules))
+                    .map_err(|err| ApiError::BadRequest(format!("{}", err)))?;
+                session.frame()
+            };
+
+            state.viewers.broadcast(id, &frame);
+            to_json(&frame)
+        }
+        (Method::Get, ["forests", id, "stats"]) => {
+            let entry = state.entry(parse_id(id)?)?;
+            let range = parse_range(query)?;
+            let session = entry.session.lock()?;
+            to_json(&statistics::yearly_statistics(&session.forest.history, &range))
+        }
+        (method, _) => Err(ApiError::NotFound(format!(
+            "no route for {} {}",
+            method,
+            request.url()
+        ))),
+    }
+}
+
+fn check_room(forests: &Registry) -> Result<(), ApiError> {
+    if forests.len() >= MAX_FORESTS {
+        return Err(ApiError::BadRequest(format!(
+            "cannot open more than {} forests",
+            MAX_FORESTS
+        )));
+    }
+
+    Ok(())
+}
+
+fn respond_error(request: Request, error: ApiError) {
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let body = serde_json::json!({ "error": error.to_string() }).to_string();
+    let response = Response::from_string(body)
+        .with_status_code(error.status())
+        .with_header(content_type);
+    let _ = request.respond(response);
+}
+
+fn to_json(value: &impl Serialize) -> Result<Value, ApiError> {
+    serde_json::to_value(value).map_err(|err| ApiError::BadRequest(format!("{}", err)))
+}
+
+/// Parses the request's JSON body. An empty body reads as `{}`.
+fn read_body<T: for<'de> Deserialize<'de>>(request: &mut Request) -> Result<T, ApiError> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|err| ApiError::BadRequest(format!("unreadable body: {}", err)))?;
+
+    let body = if body.trim().is_empty() { "{}" } else { &body };
+    serde_json::from_str(body).map_err(|err| ApiError::BadRequest(format!("invalid body: {}", err)))
+}
+
+fn parse_id(id: &str) -> Result<ForestId, ApiError> {
+    id.parse()
+        .map_err(|_| ApiError::NotFound(format!("no forest with id {}", id)))
+}
+
+fn parse_number(value: &str) -> Result<usize, ApiError> {
+    value
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("invalid number: {}", value)))
+}
+
+/// Reads `start` and `end` years from a query string such as `start=10&end=20`.
+fn parse_range(query: &str) -> Result<YearRange, ApiError> {
+    let mut range = YearRange::default();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let year = value
+            .parse()
+            .map_err(|_| ApiError::BadRequest(format!("invalid year: {}", value)))?;
+
+        match key {
+            "start" => range.start = Some(year),
+            "end" => range.end = Some(year),
+            _ => return Err(ApiError::BadRequest(format!("unknown parameter: {}", key))),
+        }
+    }
+
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(query: &str) -> Option<(Option<u32>, Option<u32>)> {
+        parse_range(query).ok().map(|range| (range.start, range.end))
+    }
+
+    #[test]
+    fn ranges_are_read_from_the_query() {
+        assert_eq!(range(""), Some((None, None)));
+        assert_eq!(range("start=10"), Some((Some(10), None)));
+        assert_eq!(range("end=20"), Some((None, Some(20))));
+        assert_eq!(range("start=10&end=20"), Some((Some(10), Some(20))));
+        assert_eq!(range("end=20&&start=10&"), Some((Some(10), Some(20))));
+    }
+
+    #[test]
+    fn malformed_ranges_are_rejected() {
+        for query in ["start", "start=", "start=-1", "end=ten", "year=3", "start=1&stop=2"] {
+            assert!(
+                matches!(parse_range(query), Err(ApiError::BadRequest(_))),
+                "{}",
+                query
+            );
+        }
+    }
+}
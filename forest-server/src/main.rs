@@ -0,0 +1,145 @@
+use forest_lib::forest::Forest;
+use forest_session::{
+    registry::{Entry, ForestId, Registry},
+    DEFAULT_HEIGHT, DEFAULT_SEED, DEFAULT_WIDTH,
+};
+use std::{
+    env,
+    net::TcpListener,
+    process,
+    sync::{Arc, Mutex},
+    thread,
+};
+use tiny_http::Server;
+
+use api::ApiError;
+use viewers::Viewers;
+
+mod api;
+mod viewers;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+/// The most forests open at once, since each keeps its own undo history.
+const MAX_FORESTS: usize = 16;
+/// The most cells a forest created over the API may have, far below the library's
+/// `MAX_CELLS` so one request cannot tie the server up building a huge map.
+const MAX_FOREST_CELLS: usize = 1 << 20;
+
+/// The forests hosted by the server and the browsers watching them.
+pub struct State {
+    forests: Mutex<Registry>,
+    viewers: Viewers,
+    /// The port frames are streamed on, which the viewer page connects to.
+    frames_port: u16,
+}
+
+impl State {
+    fn new(frames_port: u16) -> Self {
+        let mut forests = Registry::default();
+        forests.insert(None, Forest::new(DEFAULT_SEED, DEFAULT_WIDTH, DEFAULT_HEIGHT));
+
+        Self {
+            forests: Mutex::new(forests),
+            viewers: Viewers::default(),
+            frames_port,
+        }
+    }
+
+    fn entry(&self, id: ForestId) -> Result<Entry, ApiError> {
+        self.forests.lock()?.get(id).map_err(ApiError::NotFound)
+    }
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = env::args().collect();
+    let (address, frames_address) = parse_arguments(&args)?;
+
+    let server = Server::http(&address)
+        .map_err(|err| format!("could not listen on {}: {}", address, err))?;
+    let frames = TcpListener::bind(&frames_address)
+        .map_err(|err| format!("could not listen on {}: {}", frames_address, err))?;
+    let state = Arc::new(State::new(frames.local_addr()?.port()));
+    println!("serving forests on http://{}", address);
+    println!("streaming frames on ws://{}", frames_address);
+
+    let streaming = Arc::clone(&state);
+    thread::spawn(move || viewers::listen(&streaming, frames));
+
+    for request in server.incoming_requests() {
+        let state = Arc::clone(&state);
+        thread::spawn(move || api::handle(&state, request));
+    }
+
+    Ok(())
+}
+
+/// Reads `--address` and `--frames-address`. Frames default to the port after the
+/// HTTP one on the same host.
+fn parse_arguments(args: &[String]) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let mut address = DEFAULT_ADDRESS.to_string();
+    let mut frames_address = None;
+
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--address" => match args_iter.next() {
+                Some(value) => address = value.clone(),
+                None => return Err("missing value for --address".into()),
+            },
+            "--frames-address" => match args_iter.next() {
+                Some(value) => frames_address = Some(value.clone()),
+                None => return Err("missing value for --frames-address".into()),
+            },
+            other => return Err(format!("unknown argument: {}", other).into()),
+        }
+    }
+
+    let frames_address = match frames_address {
+        Some(frames_address) => frames_address,
+        None => next_port(&address)
+            .ok_or_else(|| format!("cannot pick a frames address after {}", address))?,
+    };
+
+    Ok((address, frames_address))
+}
+
+fn next_port(address: &str) -> Option<String> {
+    let (host, port) = address.rsplit_once(':')?;
+    let port: u16 = port.parse().ok()?;
+    Some(format!("{}:{}", host, port.checked_add(1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(args: &[&str]) -> Option<(String, String)> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_arguments(&args).ok()
+    }
+
+    #[test]
+    fn frames_default_to_the_next_port() {
+        let addresses = |http: &str, frames: &str| Some((http.to_string(), frames.to_string()));
+
+        assert_eq!(arguments(&["server"]), addresses("127.0.0.1:8080", "127.0.0.1:8081"));
+        assert_eq!(
+            arguments(&["server", "--address", "[::1]:9000"]),
+            addresses("[::1]:9000", "[::1]:9001")
+        );
+        assert_eq!(
+            arguments(&["server", "--frames-address", "0.0.0.0:7000"]),
+            addresses("127.0.0.1:8080", "0.0.0.0:7000")
+        );
+        assert_eq!(arguments(&["server", "--address", "0.0.0.0:65535"]), None);
+        assert_eq!(arguments(&["server", "--address", "localhost"]), None);
+        assert_eq!(arguments(&["server", "--frames-address"]), None);
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use forest_session::{
+    registry::{Entry, ForestId},
+    session::ForestFrame,
+};
+use tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::StatusCode,
+    Error, Message, WebSocket,
+};
+
+use crate::State;
+
+/// How long a silent client may take over the WebSocket handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long to wait for a frame before checking the socket for pings and closes.
+const FRAME_WAIT: Duration = Duration::from_millis(50);
+/// How long each check of the socket waits for something to arrive.
+const READ_WAIT: Duration = Duration::from_millis(1);
+
+/// The browsers watching each forest, as channels their connection threads read
+/// serialized frames from.
+#[derive(Default)]
+pub struct Viewers {
+    channels: Mutex<HashMap<ForestId, Vec<Sender<String>>>>,
+}
+
+impl Viewers {
+    fn subscribe(&self, id: ForestId) -> Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        self.channels.lock().unwrap().entry(id).or_default().push(sender);
+        receiver
+    }
+
+    /// Sends `frame` to everyone watching forest `id`, forgetting viewers that have gone.
+    pub fn broadcast(&self, id: ForestId, frame: &ForestFrame) {
+        let mut channels = self.channels.lock().unwrap();
+        let Some(senders) = channels.get_mut(&id) else {
+            return;
+        };
+
+        let frame = serde_json::to_string(frame).unwrap();
+        senders.retain(|sender| sender.send(frame.clone()).is_ok());
+    }
+
+    /// Disconnects everyone watching forest `id`.
+    pub fn close(&self, id: ForestId) {
+        self.channels.lock().unwrap().remove(&id);
+    }
+}
+
+/// Accepts viewer connections on `listener`, streaming each on its own thread.
+pub fn listen(state: &Arc<State>, listener: TcpListener) {
+    for connection in listener.incoming().flatten() {
+        let state = Arc::clone(state);
+        thread::spawn(move || stream(&state, connection));
+    }
+}
+
+/// Upgrades `connection` to a WebSocket for `/forests/{id}/frames` and streams that forest
+/// over it: a `ForestInfo` snapshot first, then a `ForestFrame` for every change, exactly as
+/// the GUI receives them. A viewer that sees a gap in the frame sequence should fetch
+/// `GET /forests/{id}` again.
+// The handshake callback's error type is tungstenite's, large as it is.
+#[allow(clippy::result_large_err)]
+fn stream(state: &State, connection: TcpStream) {
+    if connection.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).is_err() {
+        return;
+    }
+
+    let mut watched = None;
+    let accepted = tungstenite::accept_hdr(connection, |request: &Request, response: Response| {
+        let path = request.uri().path();
+        watched = find_forest(state, path);
+        match watched {
+            Some(_) => Ok(response),
+            None => {
+                let mut error = ErrorResponse::new(Some(format!("no forest frames at {}", path)));
+                *error.status_mut() = StatusCode::NOT_FOUND;
+                Err(error)
+            }
+        }
+    });
+    let Ok(mut socket) = accepted else {
+        return;
+    };
+    let Some((id, entry)) = watched else {
+        return;
+    };
+
+    // Subscribing under the session lock means every frame cut after the snapshot reaches
+    // this viewer. Frames cut before it may still arrive, and are stale by their sequence.
+    let (frames, snapshot) = {
+        let Ok(session) = entry.session.lock() else {
+            return;
+        };
+        (state.viewers.subscribe(id), session.snapshot())
+    };
+
+    let snapshot = serde_json::to_string(&snapshot).unwrap();
+    if socket.send(Message::Text(snapshot)).is_err() {
+        return;
+    }
+
+    if socket.get_ref().set_read_timeout(Some(READ_WAIT)).is_ok() {
+        forward(&mut socket, &frames);
+    }
+}
+
+/// Sends frames as they arrive while reading the socket between them, so pings are
+/// answered and the thread ends when the viewer goes away, even if the forest is paused.
+fn forward(socket: &mut WebSocket<TcpStream>, frames: &Receiver<String>) {
+    loop {
+        match frames.recv_timeout(FRAME_WAIT) {
+            Ok(frame) => {
+                if socket.send(Message::Text(frame)).is_err() {
+                    return;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                let _ = socket.close(None);
+                let _ = socket.flush();
+                return;
+            }
+        }
+
+        // Reading queues replies to pings and closes, which the flush sends.
+        match socket.read() {
+            Ok(_) => {}
+            Err(Error::Io(err))
+                if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+        if socket.flush().is_err() {
+            return;
+        }
+    }
+}
+
+/// The forest a `/forests/{id}/frames` path asks to watch, if it exists.
+fn find_forest(state: &State, path: &str) -> Option<(ForestId, Entry)> {
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    let ["forests", id, "frames"] = segments[..] else {
+        return None;
+    };
+
+    let id = id.parse().ok()?;
+    Some((id, state.entry(id).ok()?))
+}
@@ -0,0 +1,43 @@
+use forest_lib::inspect::{CellInfo, JackInfo, NeighbourCounts, TreeInfo};
+use serde::Serialize;
+
+/// `Forest::inspect` output in the shape the GUI's cell popup reads.
+#[derive(Serialize)]
+pub struct CellDetails {
+    x: usize,
+    y: usize,
+    index: usize,
+    tree: Option<TreeInfo>,
+    jack: Option<JackInfo>,
+    bear: Option<BearDetails>,
+    flags: u32,
+    neighbours: NeighbourCounts,
+}
+
+/// The bear count with its lifecycle record flattened in, which is absent unless bear
+/// lifecycle is enabled.
+#[derive(Serialize)]
+struct BearDetails {
+    count: u32,
+    age: Option<u16>,
+    food: Option<u16>,
+}
+
+pub fn cell_details(info: &CellInfo) -> CellDetails {
+    let (x, y) = info.position;
+
+    CellDetails {
+        x,
+        y,
+        index: info.index,
+        tree: info.tree,
+        jack: info.jack,
+        bear: info.bear.map(|bear| BearDetails {
+            count: bear.count,
+            age: bear.lifecycle.map(|bear| bear.age),
+            food: bear.lifecycle.map(|bear| bear.food),
+        }),
+        flags: info.flags,
+        neighbours: info.neighbours,
+    }
+}
@@ -0,0 +1,53 @@
+use forest_lib::{
+    cell::Cell,
+    edit::{Brush, Edit},
+};
+use serde::Deserialize;
+
+/// `Brush` as sent by the GUI's paint tool.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PaintBrush {
+    Cell { x: usize, y: usize },
+    Rect { x: usize, y: usize, width: usize, height: usize },
+    Circle { x: usize, y: usize, radius: usize },
+}
+
+impl From<PaintBrush> for Brush {
+    fn from(brush: PaintBrush) -> Self {
+        match brush {
+            PaintBrush::Cell { x, y } => Brush::Cell { x, y },
+            PaintBrush::Rect { x, y, width, height } => Brush::Rect { x, y, width, height },
+            PaintBrush::Circle { x, y, radius } => Brush::Circle { x, y, radius },
+        }
+    }
+}
+
+/// `Edit` as sent by the GUI's paint tool.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PaintEdit {
+    SetTree {
+        age: Cell,
+        #[serde(default)]
+        species: Cell,
+    },
+    ClearTree,
+    PlaceJack { level: Cell },
+    RemoveJack,
+    PlaceBear,
+    RemoveBear,
+}
+
+impl From<PaintEdit> for Edit {
+    fn from(edit: PaintEdit) -> Self {
+        match edit {
+            PaintEdit::SetTree { age, species } => Edit::SetTree { age, species },
+            PaintEdit::ClearTree => Edit::ClearTree,
+            PaintEdit::PlaceJack { level } => Edit::PlaceJack { level },
+            PaintEdit::RemoveJack => Edit::RemoveJack,
+            PaintEdit::PlaceBear => Edit::PlaceBear,
+            PaintEdit::RemoveBear => Edit::RemoveBear,
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::VecDeque;
+
+use forest_lib::forest::Forest;
+
+struct Snapshot {
+    forest: Forest,
+    /// Which created forest this is a state of, so rewinding never crosses into another.
+    lineage: u64,
+    /// `forest.heap_size()`, kept so the total can be updated without walking the map.
+    size: usize,
+}
+
+impl Snapshot {
+    fn new(forest: Forest, lineage: u64) -> Self {
+        let size = forest.heap_size();
+        Self {
+            forest,
+            lineage,
+            size,
+        }
+    }
+}
+
+/// A ring of earlier forests to undo back to, and the states undone since the last
+/// change to redo. Forests are cloned whole, random generator included, so resuming
+/// from a snapshot plays out exactly as it did the first time. The oldest snapshots are
+/// dropped once the kept ones take up more than `budget` bytes, though the latest is
+/// always kept so a single step can be undone on any map.
+pub struct History {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+    lineage: u64,
+    next_lineage: u64,
+    budget: usize,
+    size: usize,
+}
+
+impl History {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: vec![],
+            lineage: 0,
+            next_lineage: 1,
+            budget,
+            size: 0,
+        }
+    }
+
+    /// Changes how many bytes of snapshots are kept, dropping the oldest if need be.
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.trim();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Keeps `forest` as the state to return to on undo. Anything undone can no longer
+    /// be redone, as the current forest now branches off from it.
+    pub fn record(&mut self, forest: Forest) {
+        self.size -= self.redo.drain(..).map(|snapshot| snapshot.size).sum::<usize>();
+        self.push_undo(Snapshot::new(forest, self.lineage));
+    }
+
+    /// Records `forest` as it is about to be replaced by a newly created one.
+    pub fn record_replacement(&mut self, forest: Forest) {
+        self.record(forest);
+        self.lineage = self.next_lineage;
+        self.next_lineage += 1;
+    }
+
+    /// Records `forest` if at least `months` have passed since the last snapshot of it,
+    /// so a running simulation can be rewound without keeping every month.
+    pub fn record_every(&mut self, forest: &Forest, months: u32) {
+        let due = match self.undo.back() {
+            Some(last) if last.lineage == self.lineage => {
+                forest.months_elapsed >= last.forest.months_elapsed.saturating_add(months)
+            }
+            _ => true,
+        };
+
+        if due {
+            self.record(forest.clone());
+        }
+    }
+
+    pub fn undo(&mut self, forest: &mut Forest) -> bool {
+        let Some(snapshot) = self.undo.pop_back() else {
+            return false;
+        };
+
+        let current = self.swap(forest, snapshot);
+        self.size += current.size;
+        self.redo.push(current);
+        true
+    }
+
+    pub fn redo(&mut self, forest: &mut Forest) -> bool {
+        let Some(snapshot) = self.redo.pop() else {
+            return false;
+        };
+
+        let current = self.swap(forest, snapshot);
+        self.push_undo(current);
+        true
+    }
+
+    /// Goes back to `month` by restoring the latest snapshot at or before it and running
+    /// forward from there. The rewind itself can be undone.
+    pub fn rewind_to(&mut self, forest: &mut Forest, month: u32) -> Result<(), String> {
+        if month > forest.months_elapsed {
+            return Err(format!(
+                "month {} is after the current month {}",
+                month, forest.months_elapsed
+            ));
+        }
+
+        let Some(start) = self
+            .undo
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.lineage == self.lineage && snapshot.forest.months_elapsed <= month)
+            .map(|snapshot| snapshot.forest.clone())
+        else {
+            return Err(format!("no snapshot from month {} or earlier is kept", month));
+        };
+
+        self.record(std::mem::replace(forest, start));
+        while forest.months_elapsed < month {
+            forest.update();
+        }
+
+        Ok(())
+    }
+
+    fn push_undo(&mut self, snapshot: Snapshot) {
+        self.size += snapshot.size;
+        self.undo.push_back(snapshot);
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.size > self.budget && self.undo.len() > 1 {
+            let oldest = self.undo.pop_front().unwrap();
+            self.size -= oldest.size;
+        }
+    }
+
+    /// Puts `snapshot` in place of `forest`, returning the replaced state. The restored
+    /// snapshot no longer counts towards the size.
+    fn swap(&mut self, forest: &mut Forest, snapshot: Snapshot) -> Snapshot {
+        let lineage = std::mem::replace(&mut self.lineage, snapshot.lineage);
+        self.size -= snapshot.size;
+        Snapshot::new(std::mem::replace(forest, snapshot.forest), lineage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: usize = 64 << 20;
+
+    fn run(forest: &mut Forest, months: u32) {
+        for _ in 0..months {
+            forest.update();
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_swap_states() {
+        let mut history = History::new(BUDGET);
+        let mut forest = Forest::new(42, 20, 15);
+        let start = forest.state_hash();
+        assert!(!history.undo(&mut forest));
+
+        history.record(forest.clone());
+        run(&mut forest, 6);
+        let later = forest.state_hash();
+
+        assert!(history.undo(&mut forest));
+        assert_eq!(forest.state_hash(), start);
+        assert!(!history.can_undo() && history.can_redo());
+
+        assert!(history.redo(&mut forest));
+        assert_eq!(forest.state_hash(), later);
+        assert!(history.can_undo() && !history.can_redo());
+
+        history.undo(&mut forest);
+        history.record(forest.clone());
+        assert!(!history.can_redo());
+        assert_eq!(history.size, history.undo.iter().map(|s| s.size).sum::<usize>());
+    }
+
+    #[test]
+    fn oldest_snapshots_are_dropped_over_budget() {
+        let mut forest = Forest::new(42, 20, 15);
+        let mut history = History::new(forest.heap_size() * 3);
+
+        for _ in 0..10 {
+            history.record(forest.clone());
+            forest.update();
+        }
+        assert!(history.undo.len() <= 3);
+        assert!(history.size <= history.budget);
+        let oldest = history.undo.front().unwrap();
+        assert_eq!(oldest.forest.months_elapsed, 10 - history.undo.len() as u32);
+
+        history.set_budget(forest.heap_size());
+        assert_eq!(history.undo.len(), 1);
+
+        let mut tiny = History::new(0);
+        tiny.record(forest.clone());
+        tiny.record(forest.clone());
+        assert_eq!(tiny.undo.len(), 1);
+    }
+
+    #[test]
+    fn rewind_replays_from_the_latest_snapshot_before_the_month() {
+        let mut history = History::new(BUDGET);
+        let mut forest = Forest::new(42, 20, 15);
+        let mut expected = forest.clone();
+        run(&mut expected, 30);
+
+        while forest.months_elapsed < 60 {
+            history.record_every(&forest, 12);
+            forest.update();
+        }
+        assert_eq!(history.undo.len(), 5);
+        let end = forest.state_hash();
+
+        assert!(history.rewind_to(&mut forest, 61).is_err());
+        assert_eq!(history.rewind_to(&mut forest, 30), Ok(()));
+        assert_eq!(forest.months_elapsed, 30);
+        assert_eq!(forest.state_hash(), expected.state_hash());
+
+        history.undo(&mut forest);
+        assert_eq!(forest.state_hash(), end);
+    }
+
+    #[test]
+    fn rewind_stays_within_the_current_lineage() {
+        let mut history = History::new(BUDGET);
+        let mut forest = Forest::new(42, 20, 15);
+        history.record(forest.clone());
+        run(&mut forest, 12);
+
+        let replaced = std::mem::replace(&mut forest, Forest::new(7, 20, 15));
+        let replaced_hash = replaced.state_hash();
+        history.record_replacement(replaced);
+        run(&mut forest, 3);
+
+        assert!(history.rewind_to(&mut forest, 0).is_err());
+
+        history.record(forest.clone());
+        run(&mut forest, 3);
+        assert_eq!(history.rewind_to(&mut forest, 4), Ok(()));
+        assert_eq!(forest.months_elapsed, 4);
+
+        history.undo(&mut forest);
+        history.undo(&mut forest);
+        history.undo(&mut forest);
+        assert_eq!(forest.state_hash(), replaced_hash);
+        assert_eq!(history.lineage, 0);
+    }
+}
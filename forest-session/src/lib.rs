@@ -0,0 +1,21 @@
+//! The open forests behind the GUI and the server: their sessions, undo history and
+//! playback threads, and the serializable views both front ends send.
+
+pub mod details;
+pub mod editing;
+pub mod history;
+pub mod playback;
+pub mod registry;
+pub mod rules;
+pub mod session;
+pub mod statistics;
+pub mod stop;
+
+/// The forest the front ends open at startup, and the defaults for any creation
+/// argument left out.
+pub const DEFAULT_SEED: u64 = 123123;
+pub const DEFAULT_WIDTH: usize = 120;
+pub const DEFAULT_HEIGHT: usize = 80;
+
+/// The most months one command may advance a forest, all under its session lock.
+pub const MAX_STEP_MONTHS: u32 = 12 * 1000;
@@ -0,0 +1,158 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::session::{ForestFrame, Session};
+
+const MAX_FRAMES_PER_SECOND: u32 = 30;
+/// The fastest a simulation may play, in months per second. Each frame's months run
+/// under the session lock, so this bounds how long a frame can hold it.
+pub const MAX_SPEED: u32 = 12 * 100;
+/// How often a running simulation keeps a snapshot to undo or rewind to.
+const SNAPSHOT_EVERY_MONTHS: u32 = 12;
+
+/// Controls the background thread that advances the forest and hands each frame to its viewers.
+#[derive(Default)]
+pub struct Playback {
+    /// Bumped on every start and pause. A simulation thread exits as soon as this no
+    /// longer matches the value it was started with, so at most one thread emits frames.
+    generation: AtomicU64,
+    running: AtomicBool,
+    /// Simulated months per second.
+    speed: AtomicU32,
+}
+
+impl Playback {
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn set_speed(&self, speed: u32) -> Result<(), String> {
+        if speed == 0 {
+            return Err("speed must be at least 1 month per second".to_string());
+        }
+        if speed > MAX_SPEED {
+            return Err(format!("speed must be at most {} months per second", MAX_SPEED));
+        }
+
+        self.speed.store(speed, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Starts the simulation thread at `speed` months per second, passing every frame to
+    /// `emit`. The thread pauses itself once `emit` returns false. If one is already
+    /// running only its speed changes.
+    pub fn start<F>(
+        self: &Arc<Self>,
+        speed: u32,
+        session: Arc<Mutex<Session>>,
+        emit: F,
+    ) -> Result<(), String>
+    where
+        F: FnMut(ForestFrame) -> bool + Send + 'static,
+    {
+        self.set_speed(speed)?;
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let playback = Arc::clone(self);
+        thread::spawn(move || playback.run(generation, &session, emit));
+
+        Ok(())
+    }
+
+    pub fn pause(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    fn run(
+        &self,
+        generation: u64,
+        session: &Mutex<Session>,
+        mut emit: impl FnMut(ForestFrame) -> bool,
+    ) {
+        while self.generation.load(Ordering::SeqCst) == generation {
+            let started = Instant::now();
+            let (months, interval) = frame_plan(self.speed.load(Ordering::SeqCst));
+
+            let frame = {
+                let mut session = session.lock().unwrap();
+                session.record_every(SNAPSHOT_EVERY_MONTHS);
+                for _ in 0..months {
+                    session.forest.update();
+                }
+                session.frame()
+            };
+
+            if !emit(frame) {
+                self.pause();
+                return;
+            }
+
+            thread::sleep(interval.saturating_sub(started.elapsed()));
+        }
+    }
+}
+
+/// How many months to advance per frame and how long a frame lasts, so that fast speeds
+/// batch several months into a frame instead of flooding viewers with them.
+fn frame_plan(speed: u32) -> (u32, Duration) {
+    let speed = speed.max(1);
+    let months = speed.div_ceil(MAX_FRAMES_PER_SECOND);
+    let interval = Duration::from_secs(months as u64) / speed;
+
+    (months, interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_speeds_play_one_month_per_frame() {
+        assert_eq!(frame_plan(1), (1, Duration::from_secs(1)));
+        assert_eq!(frame_plan(12), (1, Duration::from_secs(1) / 12));
+        assert_eq!(frame_plan(MAX_FRAMES_PER_SECOND), (1, Duration::from_secs(1) / 30));
+    }
+
+    #[test]
+    fn fast_speeds_batch_months_within_the_frame_rate() {
+        for speed in [31, 100, 1000, 12_345, u32::MAX] {
+            let (months, interval) = frame_plan(speed);
+            assert!(months > 1);
+            assert!(interval >= Duration::from_secs(1) / MAX_FRAMES_PER_SECOND);
+            // The batches still add up to `speed` months a second, give or take a frame.
+            let frames = 1.0 / interval.as_secs_f64();
+            assert!((frames * months as f64 - speed as f64).abs() <= months as f64);
+        }
+    }
+
+    #[test]
+    fn zero_speed_is_treated_as_one() {
+        assert_eq!(frame_plan(0), frame_plan(1));
+    }
+
+    #[test]
+    fn speeds_outside_the_limits_are_rejected() {
+        let playback = Playback::default();
+        assert!(playback.set_speed(0).is_err());
+        assert!(playback.set_speed(MAX_SPEED + 1).is_err());
+        assert!(playback.set_speed(u32::MAX).is_err());
+        assert_eq!(playback.set_speed(MAX_SPEED), Ok(()));
+        assert_eq!(frame_plan(MAX_SPEED).0, 40);
+
+        let forest = forest_lib::forest::Forest::new(42, 12, 8);
+        let session = Arc::new(Mutex::new(Session::new(1, "test".to_string(), forest, 0)));
+        let started = Arc::new(Playback::default());
+        assert!(started.start(u32::MAX, session, |_| false).is_err());
+        assert!(!started.is_running());
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use forest_lib::forest::Forest;
+
+use crate::playback::Playback;
+use crate::session::Session;
+
+pub type ForestId = u64;
+
+/// How many bytes of undo snapshots all open forests keep between them.
+const HISTORY_BYTES: usize = 256 << 20;
+
+/// One open forest and the thread that can play it.
+#[derive(Clone)]
+pub struct Entry {
+    pub session: Arc<Mutex<Session>>,
+    pub playback: Arc<Playback>,
+}
+
+/// Every open forest, by id. Entries are handed out as clones of their
+/// `Arc`s so a command only holds the registry lock long enough to look one up.
+pub struct Registry {
+    entries: BTreeMap<ForestId, Entry>,
+    next_id: ForestId,
+    /// Split evenly between the open forests' undo histories.
+    history_bytes: usize,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::with_history_bytes(HISTORY_BYTES)
+    }
+}
+
+impl Registry {
+    pub fn with_history_bytes(history_bytes: usize) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            next_id: 1,
+            history_bytes,
+        }
+    }
+
+    /// Opens `forest` under a new id, named `forest <id>` unless a name is given.
+    pub fn insert(&mut self, name: Option<String>, forest: Forest) -> Entry {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let name = name.unwrap_or_else(|| format!("forest {}", id));
+        let session = Session::new(id, name, forest, self.history_share(self.entries.len() + 1));
+        let entry = Entry {
+            session: Arc::new(Mutex::new(session)),
+            playback: Arc::default(),
+        };
+        self.entries.insert(id, entry.clone());
+        self.rebalance();
+
+        entry
+    }
+
+    pub fn get(&self, id: ForestId) -> Result<Entry, String> {
+        self.entries
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| format!("no forest with id {}", id))
+    }
+
+    /// Closes a forest, stopping its simulation thread.
+    pub fn remove(&mut self, id: ForestId) -> Result<(), String> {
+        let entry = self
+            .entries
+            .remove(&id)
+            .ok_or_else(|| format!("no forest with id {}", id))?;
+        entry.playback.pause();
+        self.rebalance();
+
+        Ok(())
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> + '_ {
+        self.entries.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn history_share(&self, forests: usize) -> usize {
+        self.history_bytes / forests.max(1)
+    }
+
+    /// Gives every open forest an equal share of the history budget again.
+    fn rebalance(&self) {
+        let share = self.history_share(self.entries.len());
+        for entry in self.entries.values() {
+            // A session poisoned by a panic elsewhere still has a history to shrink.
+            let mut session = entry.session.lock().unwrap_or_else(PoisonError::into_inner);
+            session.set_history_bytes(share);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forest() -> Forest {
+        Forest::new(42, 12, 8)
+    }
+
+    #[test]
+    fn forests_get_increasing_ids_and_default_names() {
+        let mut registry = Registry::default();
+        let first = registry.insert(None, forest());
+        let second = registry.insert(Some("named".to_string()), forest());
+
+        assert_eq!(first.session.lock().unwrap().id, 1);
+        assert_eq!(first.session.lock().unwrap().name, "forest 1");
+        assert_eq!(second.session.lock().unwrap().id, 2);
+        assert_eq!(second.session.lock().unwrap().name, "named");
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn removed_forests_are_gone_and_their_ids_not_reused() {
+        let mut registry = Registry::default();
+        registry.insert(None, forest());
+        registry.insert(None, forest());
+
+        assert_eq!(registry.remove(1), Ok(()));
+        assert!(registry.get(1).is_err());
+        assert!(registry.get(2).is_ok());
+        assert_eq!(registry.remove(1), Err("no forest with id 1".to_string()));
+
+        let entry = registry.insert(None, forest());
+        assert_eq!(entry.session.lock().unwrap().id, 3);
+        assert_eq!(registry.entries().count(), 2);
+    }
+
+    #[test]
+    fn opening_forests_shrinks_each_ones_history() {
+        let mut registry = Registry::with_history_bytes(2 * forest().heap_size());
+        let first = registry.insert(None, forest());
+        {
+            let mut session = first.session.lock().unwrap();
+            session.record();
+            session.record();
+        }
+
+        registry.insert(None, forest());
+        let mut session = first.session.lock().unwrap();
+        assert!(session.undo().is_ok());
+        assert!(session.undo().is_err());
+    }
+}
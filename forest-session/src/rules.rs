@@ -0,0 +1,67 @@
+use forest_lib::rules::{Density, Rules};
+use serde::{Deserialize, Serialize};
+
+/// `Rules` as shown in the GUI's rule editor, with the policy and movement strategies in
+/// the same text form the CLI accepts.
+#[derive(Serialize, Deserialize)]
+pub struct RuleSettings {
+    pub bear_lifecycle: bool,
+    pub economy: bool,
+    pub policy: String,
+    pub jack_movement: String,
+    pub bear_movement: String,
+    pub density: DensitySettings,
+    /// Only used by builds with the `parallel` feature.
+    #[serde(default)]
+    pub tree_threads: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DensitySettings {
+    pub trees: f64,
+    pub jacks: f64,
+    pub bears: f64,
+}
+
+/// The `f64` with the same shortest decimal form as `value`, so a density of 0.02 reaches
+/// the editor as 0.02 rather than 0.019999999552965164.
+fn widen(value: f32) -> f64 {
+    value.to_string().parse().unwrap()
+}
+
+impl From<&Rules> for RuleSettings {
+    fn from(rules: &Rules) -> Self {
+        Self {
+            bear_lifecycle: rules.bear_lifecycle,
+            economy: rules.economy,
+            policy: rules.policy.to_string(),
+            jack_movement: rules.jack_movement.to_string(),
+            bear_movement: rules.bear_movement.to_string(),
+            density: DensitySettings {
+                trees: widen(rules.density.trees),
+                jacks: widen(rules.density.jacks),
+                bears: widen(rules.density.bears),
+            },
+            tree_threads: rules.tree_threads,
+        }
+    }
+}
+
+impl RuleSettings {
+    /// Parses the settings over `rules`, which supplies anything the editor doesn't show.
+    pub fn into_rules(self, mut rules: Rules) -> Result<Rules, String> {
+        rules.bear_lifecycle = self.bear_lifecycle;
+        rules.economy = self.economy;
+        rules.policy = self.policy.parse().map_err(|err| format!("{}", err))?;
+        rules.jack_movement = self.jack_movement.parse().map_err(|err| format!("{}", err))?;
+        rules.bear_movement = self.bear_movement.parse().map_err(|err| format!("{}", err))?;
+        rules.density = Density {
+            trees: self.density.trees as f32,
+            jacks: self.density.jacks as f32,
+            bears: self.density.bears as f32,
+        };
+        rules.tree_threads = self.tree_threads;
+
+        Ok(rules)
+    }
+}
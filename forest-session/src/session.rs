@@ -0,0 +1,233 @@
+use forest_lib::{cell, forest::Forest};
+use serde::Serialize;
+
+use crate::history::History;
+use crate::registry::ForestId;
+
+/// Everything shown next to the map, and in the table comparing open forests.
+#[derive(Clone, Serialize)]
+pub struct ForestSummary {
+    id: ForestId,
+    name: String,
+    width: usize,
+    height: usize,
+    months_elapsed: u32,
+    yearly_lumber: u32,
+    yearly_mauls: u32,
+    lumber_stockpile: u64,
+    funds: i64,
+    policy: String,
+    jack_movement: String,
+    bear_movement: String,
+    trees: usize,
+    jacks: usize,
+    bears: usize,
+    can_undo: bool,
+    can_redo: bool,
+}
+
+impl ForestSummary {
+    fn new(session: &Session) -> Self {
+        let forest = &session.forest;
+        Self {
+            id: session.id,
+            name: session.name.clone(),
+            width: forest.width,
+            height: forest.height,
+            months_elapsed: forest.months_elapsed,
+            yearly_lumber: forest.yearly_lumber,
+            yearly_mauls: forest.yearly_mauls,
+            lumber_stockpile: forest.economy.stockpile,
+            funds: forest.economy.funds,
+            policy: forest.rules.policy.to_string(),
+            jack_movement: forest.rules.jack_movement.to_string(),
+            bear_movement: forest.rules.bear_movement.to_string(),
+            trees: forest.tree_count(),
+            jacks: forest.jack_count(),
+            bears: forest.bear_count(),
+            can_undo: session.history.can_undo(),
+            can_redo: session.history.can_redo(),
+        }
+    }
+}
+
+/// A full copy of the map, sent when a forest is created and whenever a viewer resyncs.
+#[derive(Clone, Serialize)]
+pub struct ForestInfo {
+    /// The last frame this snapshot already includes.
+    sequence: u64,
+    map: Vec<u16>,
+    #[serde(flatten)]
+    summary: ForestSummary,
+}
+
+/// The cells changed since the previous frame, as flat `[index, cell, index, cell, ..]`
+/// pairs with cells in the legacy `u16` layout.
+#[derive(Clone, Serialize)]
+pub struct ForestFrame {
+    /// One more than the previous frame's. A viewer that sees a gap has missed changes and
+    /// should resync.
+    sequence: u64,
+    changes: Vec<u64>,
+    #[serde(flatten)]
+    summary: ForestSummary,
+}
+
+/// The forest shared by the commands and the simulation thread, with the number of the
+/// last frame cut from it and the states it can be undone to.
+pub struct Session {
+    pub id: ForestId,
+    pub name: String,
+    pub forest: Forest,
+    sequence: u64,
+    history: History,
+}
+
+impl Session {
+    /// Opens `forest`, keeping up to `history_bytes` of earlier states for undo and rewind.
+    pub fn new(id: ForestId, name: String, forest: Forest, history_bytes: usize) -> Self {
+        let mut session = Self {
+            id,
+            name,
+            forest,
+            sequence: 0,
+            history: History::new(history_bytes),
+        };
+        session.forest.take_changes();
+        session
+    }
+
+    /// Swaps in a new forest, keeping the old one for undo. The sequence keeps counting
+    /// up so frames of the old forest that are still in flight look stale to viewers.
+    pub fn replace(&mut self, forest: Forest) -> ForestInfo {
+        let old = std::mem::replace(&mut self.forest, forest);
+        self.history.record_replacement(old);
+        self.resync()
+    }
+
+    pub fn set_history_bytes(&mut self, history_bytes: usize) {
+        self.history.set_budget(history_bytes);
+    }
+
+    pub fn summary(&self) -> ForestSummary {
+        ForestSummary::new(self)
+    }
+
+    /// Keeps the current forest for undo, ahead of changing it.
+    pub fn record(&mut self) {
+        self.history.record(self.forest.clone());
+    }
+
+    /// Keeps the current forest for undo if `months` have passed since the last snapshot.
+    pub fn record_every(&mut self, months: u32) {
+        self.history.record_every(&self.forest, months);
+    }
+
+    /// Runs `change` on the forest, keeping the state before it for undo if it succeeds.
+    pub fn change<T, E>(&mut self, change: impl FnOnce(&mut Forest) -> Result<T, E>) -> Result<T, E> {
+        let before = self.forest.clone();
+        let result = change(&mut self.forest)?;
+        self.history.record(before);
+        Ok(result)
+    }
+
+    pub fn undo(&mut self) -> Result<ForestInfo, String> {
+        match self.history.undo(&mut self.forest) {
+            true => Ok(self.resync()),
+            false => Err("nothing to undo".to_string()),
+        }
+    }
+
+    pub fn redo(&mut self) -> Result<ForestInfo, String> {
+        match self.history.redo(&mut self.forest) {
+            true => Ok(self.resync()),
+            false => Err("nothing to redo".to_string()),
+        }
+    }
+
+    pub fn rewind_to(&mut self, month: u32) -> Result<ForestInfo, String> {
+        self.history.rewind_to(&mut self.forest, month)?;
+        Ok(self.resync())
+    }
+
+    /// A full copy of the map as of the last frame. Changes made since then stay pending
+    /// for the next frame, which is harmless as frames carry whole cell values.
+    pub fn snapshot(&self) -> ForestInfo {
+        ForestInfo {
+            sequence: self.sequence,
+            map: self.forest.legacy_cells().collect(),
+            summary: self.summary(),
+        }
+    }
+
+    /// Starts a new frame and returns a full copy of the map for it.
+    pub fn resync(&mut self) -> ForestInfo {
+        self.forest.take_changes();
+        self.sequence += 1;
+        self.snapshot()
+    }
+
+    /// Cuts a frame holding only the cells changed since the previous one.
+    pub fn frame(&mut self) -> ForestFrame {
+        let changes = self
+            .forest
+            .take_changes()
+            .into_iter()
+            .flat_map(|(index, cell)| [index as u64, cell::to_legacy(cell) as u64])
+            .collect();
+        self.sequence += 1;
+
+        ForestFrame {
+            sequence: self.sequence,
+            changes,
+            summary: self.summary(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session::new(1, "test".to_string(), Forest::new(42, 20, 15), 64 << 20)
+    }
+
+    #[test]
+    fn frames_and_resyncs_share_one_sequence() {
+        let mut session = session();
+        assert_eq!(session.snapshot().sequence, 0);
+
+        let frame = session.frame();
+        assert_eq!(frame.sequence, 1);
+        assert!(frame.changes.is_empty());
+
+        session.forest.update();
+        assert_eq!(session.frame().sequence, 2);
+        assert_eq!(session.resync().sequence, 3);
+        assert_eq!(session.snapshot().sequence, 3);
+
+        session.record();
+        session.forest.update();
+        assert_eq!(session.undo().map(|info| info.sequence), Ok(4));
+        assert_eq!(session.redo().map(|info| info.sequence), Ok(5));
+        assert_eq!(session.replace(Forest::new(7, 20, 15)).sequence, 6);
+        assert_eq!(session.frame().sequence, 7);
+    }
+
+    #[test]
+    fn frames_bring_a_snapshot_up_to_date() {
+        let mut session = session();
+        let mut map = session.snapshot().map;
+
+        for _ in 0..24 {
+            session.forest.update();
+            let frame = session.frame();
+            for change in frame.changes.chunks(2) {
+                map[change[0] as usize] = change[1] as u16;
+            }
+        }
+
+        assert_eq!(map, session.forest.legacy_cells().collect::<Vec<_>>());
+    }
+}
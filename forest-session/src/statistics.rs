@@ -0,0 +1,55 @@
+use forest_lib::stats::YearlyStats;
+use serde::Deserialize;
+
+/// The years to return from `get_statistics`, from `start` up to but not including `end`.
+/// Either end may be left open.
+#[derive(Default, Deserialize)]
+pub struct YearRange {
+    pub start: Option<u32>,
+    pub end: Option<u32>,
+}
+
+impl YearRange {
+    fn contains(&self, year: u32) -> bool {
+        self.start.is_none_or(|start| year >= start) && self.end.is_none_or(|end| year < end)
+    }
+}
+
+/// The forest's yearly history within `range`, oldest first, for charting.
+pub fn yearly_statistics(history: &[YearlyStats], range: &YearRange) -> Vec<YearlyStats> {
+    history
+        .iter()
+        .filter(|stats| range.contains(stats.year))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn year_ranges_include_the_start_but_not_the_end() {
+        let range = YearRange { start: Some(3), end: Some(5) };
+        assert!(!range.contains(2));
+        assert!(range.contains(3) && range.contains(4));
+        assert!(!range.contains(5));
+
+        assert!(YearRange::default().contains(0));
+        assert!(YearRange { start: None, end: Some(1) }.contains(0));
+        assert!(YearRange { start: Some(7), end: None }.contains(u32::MAX));
+        assert!(!YearRange { start: Some(4), end: Some(4) }.contains(4));
+    }
+
+    #[test]
+    fn statistics_are_filtered_by_year() {
+        let history: Vec<_> = (1..=6).map(YearlyStats::new).collect();
+        let years = |range: YearRange| -> Vec<u32> {
+            yearly_statistics(&history, &range).iter().map(|stats| stats.year).collect()
+        };
+
+        assert_eq!(years(YearRange::default()), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(years(YearRange { start: Some(2), end: Some(4) }), [2, 3]);
+        assert_eq!(years(YearRange { start: Some(10), end: None }), []);
+    }
+}
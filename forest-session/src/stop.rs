@@ -0,0 +1,24 @@
+use forest_lib::forest::Forest;
+use serde::Deserialize;
+
+/// When `run_until` should stop advancing the forest.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StopCondition {
+    /// Stops once the forest has run for `month` months in total.
+    Month { month: u32 },
+    NoBears,
+    NoJacks,
+    NoTrees,
+}
+
+impl StopCondition {
+    pub fn is_met(&self, forest: &Forest) -> bool {
+        match self {
+            StopCondition::Month { month } => forest.months_elapsed >= *month,
+            StopCondition::NoBears => forest.bear_count() == 0,
+            StopCondition::NoJacks => forest.jack_count() == 0,
+            StopCondition::NoTrees => forest.tree_count() == 0,
+        }
+    }
+}